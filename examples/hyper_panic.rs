@@ -16,7 +16,6 @@ use std::time::Duration;
 
 use bytes::Bytes;
 use http_body_util::Full;
-use hyper::server::conn::http1::Builder;
 use hyper::service::service_fn;
 use hyper::{body::Incoming, Request, Response};
 use hyper_util::rt::TokioIo;
@@ -30,59 +29,30 @@ async fn main() {
         .with(EnvFilter::from_default_env())
         .init();
 
-    // oneshot channel used to be able to trigger a shutdown
-    // in case of an unexpected task exit
-    let (task_exit_tx, task_exit_rx) = tokio::sync::oneshot::channel::<()>();
+    let shutdown = tokio_graceful::Shutdown::default();
 
-    let shutdown = tokio_graceful::Shutdown::new(async move {
-        tokio::select! {
-            _ = task_exit_rx => {
-                tracing::warn!("critical task exit observed, shutting down");
-            }
-            _ = tokio_graceful::default_signal() => {
-                tracing::info!("external exit signal received, shutting down");
-            }
-        }
-    });
-
-    // Short for `shutdown.guard().into_spawn_task_fn(serve_tcp)`
+    // Spawn the server as a critical task, such that a shutdown is triggered
+    // in case it exits unexpectedly: returning, panicking or being aborted.
+    // A common reason for this could be because you have an issue at server setup
+    // (e.g. port that you try to bind to is already in use).
+    //
+    // Short for `shutdown.guard().spawn_critical_task_fn(serve_tcp)`
     // In case you only wish to pass in a future (in contrast to a function)
     // as you do not care about being able to use the linked guard,
-    // you can also use [`Shutdown::spawn_task`](https://docs.rs/tokio-graceful/latest/tokio_graceful/struct.Shutdown.html#method.spawn_task).
-    let server_handle = shutdown.spawn_task_fn(serve_tcp);
-
-    // spawn a task that will trigger a shutdown in case of an error with our server,
-    // a common reason for this could be because you have an issue at server setup
-    // (e.g. port that you try to bind to is already in use)
-    let shutdown_err_guard = shutdown.guard_weak();
-    tokio::spawn(async move {
-        tokio::select! {
-            _ = shutdown_err_guard.cancelled() => {
-                // shutdown signal received, do nothing but exit this task
-                return;
-            }
-            result = server_handle => {
-                match result {
-                    Ok(_) => {
-                        tracing::info!("server exited, triggering manual shutdown");
-                    }
-                    Err(err) => {
-                        tracing::error!(error = &err as &dyn std::error::Error, "server exited, triggering error shutdown");
-                    }
-                }
-            }
-        }
-        task_exit_tx.send(()).unwrap();
-    });
+    // you can also use [`Shutdown::spawn_critical_task`](https://docs.rs/tokio-graceful/latest/tokio_graceful/struct.Shutdown.html#method.spawn_critical_task).
+    shutdown.spawn_critical_task_fn(serve_tcp);
 
     // use [`Shutdown::shutdown`](https://docs.rs/tokio-graceful/latest/tokio_graceful/struct.Shutdown.html#method.shutdown)
     // to wait for all guards to drop without any limit on how long to wait.
     match shutdown.shutdown_with_limit(Duration::from_secs(10)).await {
-        Ok(elapsed) => {
+        Ok(report) => {
             tracing::info!(
                 "shutdown: gracefully {}s after shutdown signal received",
-                elapsed.as_secs_f64()
+                report.drain().as_secs_f64()
             );
+            if let Some(tokio_graceful::ShutdownReason::CriticalTask(exit)) = report.reason() {
+                tracing::warn!("shutdown was caused by critical task exit: {exit}");
+            }
         }
         Err(e) => {
             tracing::warn!(
@@ -121,24 +91,15 @@ async fn serve_tcp(shutdown_guard: tokio_graceful::ShutdownGuard) {
         let stream = TokioIo::new(stream);
 
         shutdown_guard.spawn_task_fn(move |guard: tokio_graceful::ShutdownGuard| async move {
-            let conn = Builder::new()
-                .serve_connection(stream, service_fn(hello));
-            let mut conn = std::pin::pin!(conn);
-
-            tokio::select! {
-                _ = guard.cancelled() => {
-                    tracing::info!("signal received: initiate graceful shutdown");
-                    conn.as_mut().graceful_shutdown();
-                }
-                result = conn.as_mut() => {
-                    if let Err(err) = result {
-                        tracing::error!(error = &err as &dyn std::error::Error, "conn exited with error");
-                    }
-                    return;
-                }
-            }
-            if let Err(err) = conn.as_mut().await  {
-                tracing::error!(error = &err as &dyn std::error::Error, "conn exited with error after graceful shutdown");
+            // serves the connection until it is closed or the shutdown is cancelled,
+            // after which the connection is shutdown gracefully
+            if let Err(err) =
+                tokio_graceful::serve_connection(stream, service_fn(hello), guard).await
+            {
+                tracing::error!(
+                    error = &*err as &dyn std::error::Error,
+                    "conn exited with error"
+                );
             }
         });
     }
@@ -16,7 +16,6 @@ use std::time::Duration;
 
 use bytes::Bytes;
 use http_body_util::Full;
-use hyper::server::conn::http1::Builder;
 use hyper::service::service_fn;
 use hyper::{body::Incoming, Request, Response};
 use hyper_util::rt::TokioIo;
@@ -43,10 +42,10 @@ async fn main() {
     // use [`Shutdown::shutdown`](https://docs.rs/tokio-graceful/latest/tokio_graceful/struct.Shutdown.html#method.shutdown)
     // to wait for all guards to drop without any limit on how long to wait.
     match shutdown.shutdown_with_limit(Duration::from_secs(10)).await {
-        Ok(elapsed) => {
+        Ok(report) => {
             tracing::info!(
                 "shutdown: gracefully {}s after shutdown signal received",
-                elapsed.as_secs_f64()
+                report.drain().as_secs_f64()
             );
         }
         Err(e) => {
@@ -83,24 +82,15 @@ async fn serve_tcp(shutdown_guard: tokio_graceful::ShutdownGuard) {
         let stream = TokioIo::new(stream);
 
         shutdown_guard.spawn_task_fn(move |guard: tokio_graceful::ShutdownGuard| async move {
-            let conn = Builder::new()
-                .serve_connection(stream, service_fn(hello));
-            let mut conn = std::pin::pin!(conn);
-
-            tokio::select! {
-                _ = guard.cancelled() => {
-                    tracing::info!("signal received: initiate graceful shutdown");
-                    conn.as_mut().graceful_shutdown();
-                }
-                result = conn.as_mut() => {
-                    if let Err(err) = result {
-                        tracing::error!(error = &err as &dyn std::error::Error, "conn exited with error");
-                    }
-                    return;
-                }
-            }
-            if let Err(err) = conn.as_mut().await  {
-                tracing::error!(error = &err as &dyn std::error::Error, "conn exited with error after graceful shutdown");
+            // serves the connection until it is closed or the shutdown is cancelled,
+            // after which the connection is shutdown gracefully
+            if let Err(err) =
+                tokio_graceful::serve_connection(stream, service_fn(hello), guard).await
+            {
+                tracing::error!(
+                    error = &*err as &dyn std::error::Error,
+                    "conn exited with error"
+                );
             }
         });
     }
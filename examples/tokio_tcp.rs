@@ -8,6 +8,7 @@
 use std::time::Duration;
 
 use tokio::net::TcpListener;
+use tokio_graceful::GracefulListener;
 use tracing_subscriber::{fmt, prelude::*, EnvFilter};
 
 #[tokio::main]
@@ -28,10 +29,10 @@ async fn main() {
     // use [`Shutdown::shutdown`](https://docs.rs/tokio-graceful/latest/tokio_graceful/struct.Shutdown.html#method.shutdown)
     // to wait for all guards to drop without any limit on how long to wait.
     match shutdown.shutdown_with_limit(Duration::from_secs(10)).await {
-        Ok(elapsed) => {
+        Ok(report) => {
             tracing::info!(
                 "shutdown: gracefully {}s after shutdown signal received",
-                elapsed.as_secs_f64()
+                report.drain().as_secs_f64()
             );
         }
         Err(e) => {
@@ -46,32 +47,29 @@ async fn serve_tcp(shutdown_guard: tokio_graceful::ShutdownGuard) {
     let listener = TcpListener::bind("127.0.0.1:8080").await.unwrap();
     tracing::info!("listening on {}", listener.local_addr().unwrap());
 
-    loop {
-        let shutdown_guard = shutdown_guard.clone();
-        tokio::select! {
-            _ = shutdown_guard.cancelled() => {
-                tracing::info!("signal received: initiate graceful shutdown");
-                break;
+    // the listener stops accepting connections once the shutdown signal is received,
+    // and links each accepted connection to a (cloned) shutdown guard
+    let mut listener = GracefulListener::new(listener, &shutdown_guard);
+    while let Some(result) = listener.accept().await {
+        match result {
+            Ok((mut socket, _)) => {
+                tokio::spawn(async move {
+                    // NOTE, make sure to pass the shutdown guard of the connection (see `Connection::guard`)
+                    // to this function or any of its children in case you wish to be able to cancel a long running process should the
+                    // shutdown signal be received and you know that your task might not finish on time.
+                    // This allows you to at least leave it behind in a consistent state such that another
+                    // process can pick up where you left that task.
+                    let (mut reader, mut writer) = tokio::io::split(&mut socket);
+                    let _ = tokio::io::copy(&mut reader, &mut writer).await;
+                });
             }
-            result = listener.accept() => {
-                match result {
-                    Ok((socket, _)) => {
-                        tokio::spawn(async move {
-                            // NOTE, make sure to pass a clone of the shutdown guard to this function
-                            // or any of its children in case you wish to be able to cancel a long running process should the
-                            // shutdown signal be received and you know that your task might not finish on time.
-                            // This allows you to at least leave it behind in a consistent state such that another
-                            // process can pick up where you left that task.
-                            let (mut reader, mut writer) = tokio::io::split(socket);
-                            let _ = tokio::io::copy(&mut reader, &mut writer).await;
-                            drop(shutdown_guard);
-                        });
-                    }
-                    Err(e) => {
-                        tracing::warn!("accept error: {:?}", e);
-                    }
-                }
+            Err(e) => {
+                tracing::warn!("accept error: {:?}", e);
             }
         }
     }
+    tracing::info!(
+        "signal received: initiate graceful shutdown: {} open connection(s)",
+        listener.open_connections()
+    );
 }
@@ -0,0 +1,176 @@
+use std::{
+    future::Future,
+    marker::PhantomData,
+    pin::Pin,
+    task::{Context, Poll},
+};
+
+use pin_project_lite::pin_project;
+
+use crate::{trigger::Receiver, ShutdownGuard, WeakShutdownGuard};
+
+/// A [`Future`] that completes once cancellation (shutdown) is requested,
+/// borrowing the guard it was created from.
+///
+/// Returned by [`ShutdownGuard::cancelled`], [`ShutdownGuard::shutdown_signal_triggered`]
+/// and their [`WeakShutdownGuard`] counterparts. It is [`Unpin`], such that it can
+/// be stored in a struct field or be polled from a hand-written [`Future`] without boxing.
+///
+/// See [`CancelledOwned`] for a variant which does not borrow the guard.
+///
+/// # Cancel safety
+///
+/// This future is cancel safe.
+///
+/// [`ShutdownGuard::cancelled`]: crate::ShutdownGuard::cancelled
+/// [`ShutdownGuard::shutdown_signal_triggered`]: crate::ShutdownGuard::shutdown_signal_triggered
+#[derive(Debug)]
+#[must_use = "futures do nothing unless you `.await` or poll them"]
+pub struct Cancelled<'a> {
+    rx: Receiver,
+    _guard: PhantomData<&'a WeakShutdownGuard>,
+}
+
+impl<'a> Cancelled<'a> {
+    pub(crate) fn new(rx: Receiver) -> Self {
+        Self {
+            rx,
+            _guard: PhantomData,
+        }
+    }
+}
+
+impl Future for Cancelled<'_> {
+    type Output = ();
+
+    fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        Pin::new(&mut self.rx).poll(cx)
+    }
+}
+
+/// A [`Future`] that completes once cancellation (shutdown) is requested,
+/// which (in contrast to [`Cancelled`]) does not borrow the guard it was created from.
+///
+/// Returned by [`ShutdownGuard::cancelled_owned`], [`ShutdownGuard::shutdown_signal_triggered_owned`],
+/// [`WeakShutdownGuard::into_cancelled`] and their counterparts. It is [`Unpin`] and `'static`,
+/// such that it can be stored in a struct field or be polled from a hand-written [`Future`]
+/// without boxing.
+///
+/// # Cancel safety
+///
+/// This future is cancel safe.
+///
+/// [`ShutdownGuard::cancelled_owned`]: crate::ShutdownGuard::cancelled_owned
+/// [`ShutdownGuard::shutdown_signal_triggered_owned`]: crate::ShutdownGuard::shutdown_signal_triggered_owned
+#[derive(Debug, Clone)]
+#[must_use = "futures do nothing unless you `.await` or poll them"]
+pub struct CancelledOwned {
+    rx: Receiver,
+}
+
+impl CancelledOwned {
+    pub(crate) fn new(rx: Receiver) -> Self {
+        Self { rx }
+    }
+}
+
+impl Future for CancelledOwned {
+    type Output = ();
+
+    fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        Pin::new(&mut self.rx).poll(cx)
+    }
+}
+
+pin_project! {
+    /// A [`Future`] which runs the wrapped future until it completes,
+    /// resolving to `Some` of its output, or until cancellation (shutdown)
+    /// is requested, resolving to `None`, in which case the wrapped future is dropped.
+    ///
+    /// In case both are ready at the same time, the cancellation takes precedence.
+    ///
+    /// Returned by [`ShutdownGuard::run_until_cancelled`],
+    /// [`ShutdownGuard::run_until_shutdown_signal_triggered`],
+    /// [`FutureExt::until_cancelled`] and their counterparts.
+    ///
+    /// # Cancel safety
+    ///
+    /// This future is as cancel safe as the wrapped future.
+    #[derive(Debug)]
+    #[must_use = "futures do nothing unless you `.await` or poll them"]
+    pub struct UntilCancelled<F> {
+        #[pin]
+        future: F,
+        cancelled: CancelledOwned,
+    }
+}
+
+impl<F> UntilCancelled<F> {
+    pub(crate) fn new(future: F, cancelled: CancelledOwned) -> Self {
+        Self { future, cancelled }
+    }
+}
+
+impl<F: Future> Future for UntilCancelled<F> {
+    type Output = Option<F::Output>;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        let this = self.project();
+        if Pin::new(this.cancelled).poll(cx).is_ready() {
+            return Poll::Ready(None);
+        }
+        this.future.poll(cx).map(Some)
+    }
+}
+
+/// An extension trait for [`Future`]s, to run them until
+/// the [`Shutdown`] of the given guard is cancelled.
+///
+/// ```
+/// use tokio_graceful::{FutureExt, Shutdown};
+///
+/// # async fn example() {
+/// let shutdown = Shutdown::no_signal();
+/// let guard = shutdown.guard();
+/// let output = async { 42 }.until_cancelled(&guard).await;
+/// // a shutdown without a signal is cancelled from the start
+/// assert_eq!(output, None);
+/// # }
+/// ```
+///
+/// [`Shutdown`]: crate::Shutdown
+pub trait FutureExt: Future + Sized {
+    /// Runs the future until it completes, resolving to `Some` of its output,
+    /// or until cancellation (shutdown) is requested and the delay (if any)
+    /// duration has been awaited, resolving to `None`.
+    ///
+    /// See [`ShutdownGuard::run_until_cancelled`] for more information.
+    fn until_cancelled<G: AsRef<WeakShutdownGuard>>(self, guard: &G) -> UntilCancelled<Self> {
+        UntilCancelled::new(self, guard.as_ref().cancelled_owned())
+    }
+
+    /// Runs the future until it completes, resolving to `Some` of its output,
+    /// or until cancellation (shutdown) is requested, resolving to `None`.
+    ///
+    /// See [`ShutdownGuard::run_until_shutdown_signal_triggered`] for more information.
+    fn until_shutdown_signal_triggered<G: AsRef<WeakShutdownGuard>>(
+        self,
+        guard: &G,
+    ) -> UntilCancelled<Self> {
+        UntilCancelled::new(self, guard.as_ref().shutdown_signal_triggered_owned())
+    }
+}
+
+impl<F: Future> FutureExt for F {}
+
+impl AsRef<WeakShutdownGuard> for ShutdownGuard {
+    fn as_ref(&self) -> &WeakShutdownGuard {
+        self.weak()
+    }
+}
+
+impl AsRef<WeakShutdownGuard> for WeakShutdownGuard {
+    fn as_ref(&self) -> &WeakShutdownGuard {
+        self
+    }
+}
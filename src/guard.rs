@@ -1,8 +1,13 @@
-use std::{future::Future, mem::ManuallyDrop};
+use std::{borrow::Cow, fmt, future::Future, mem::ManuallyDrop, panic::Location};
 
 use crate::{
+    future::{Cancelled, CancelledOwned, UntilCancelled},
+    registry::Registry,
+    reload::{ReloadGuard, ReloadTrigger},
     sync::{Arc, AtomicUsize, JoinHandle, Ordering},
+    task::{spawn_task, CriticalTask, TaskSpan, TaskTracker},
     trigger::{Receiver, Sender},
+    Lifecycle, Readiness, Shutdown, ShutdownReason, ShutdownTrigger,
 };
 
 /// A guard, linked to a [`Shutdown`] struct,
@@ -12,10 +17,32 @@ use crate::{
 /// and can be downgraded to a [`WeakShutdownGuard`] to
 /// no longer prevent the [`Shutdown::shutdown`] future from completing.
 ///
+/// A guard can be given a name using [`ShutdownGuard::named`],
+/// such that it can be identified using [`Shutdown::live_guards`]
+/// in case it is still alive when it is not expected to be.
+///
+/// Tasks spawned using a guard (e.g. [`ShutdownGuard::spawn_task`]) run within
+/// a `task` span (at debug level) carrying the name of the guard, if named.
+/// Within this span it is traced when the task is first polled after the cancellation
+/// and when the guard handed to the task is released, together with the elapsed times.
+///
 /// [`Shutdown`]: crate::Shutdown
 /// [`Shutdown::shutdown`]: crate::Shutdown::shutdown
+/// [`Shutdown::live_guards`]: crate::Shutdown::live_guards
 #[derive(Debug)]
-pub struct ShutdownGuard(ManuallyDrop<WeakShutdownGuard>);
+pub struct ShutdownGuard {
+    weak: ManuallyDrop<WeakShutdownGuard>,
+    registry_key: Option<usize>,
+    #[cfg(feature = "leak-detection")]
+    leak_key: Option<usize>,
+    /// Whether the guard is held internally, e.g. by the [`Shutdown`] or one of its phases,
+    /// in which case it is neither counted in the metrics nor tracked for leaks.
+    #[cfg_attr(not(feature = "metrics"), allow(dead_code))]
+    internal: bool,
+    /// The span of the task this guard was handed to (if any),
+    /// within which the release of this guard is traced.
+    task_span: Option<TaskSpan>,
+}
 
 /// A weak guard, linked to a [`Shutdown`] struct,
 /// is similar to a [`ShutdownGuard`] but does not
@@ -29,23 +56,45 @@ pub struct WeakShutdownGuard {
     pub(crate) shutdown_signal_trigger_rx: Option<Receiver>,
     pub(crate) zero_tx: Sender,
     pub(crate) ref_count: Arc<AtomicUsize>,
+    pub(crate) shutdown_trigger: ShutdownTrigger,
+    pub(crate) registry: Registry,
+    pub(crate) task_tracker: Option<TaskTracker>,
+    pub(crate) reload_trigger: ReloadTrigger,
 }
 
 impl ShutdownGuard {
+    #[allow(clippy::too_many_arguments)]
     pub(crate) fn new(
         trigger_rx: Receiver,
         shutdown_signal_trigger_rx: Option<Receiver>,
         zero_tx: Sender,
         ref_count: Arc<AtomicUsize>,
+        shutdown_trigger: ShutdownTrigger,
+        registry: Registry,
+        task_tracker: Option<TaskTracker>,
+        reload_trigger: ReloadTrigger,
     ) -> Self {
         let value = ref_count.fetch_add(1, Ordering::SeqCst);
         tracing::trace!("new shutdown guard: ref_count+1: {}", value + 1);
-        Self(ManuallyDrop::new(WeakShutdownGuard::new(
+        let weak = WeakShutdownGuard::new(
             trigger_rx,
             shutdown_signal_trigger_rx,
             zero_tx,
             ref_count,
-        )))
+            shutdown_trigger,
+            registry,
+            task_tracker,
+            reload_trigger,
+        );
+        Self {
+            // internal guards are not tracked, as they are dropped by the drain itself
+            #[cfg(feature = "leak-detection")]
+            leak_key: None,
+            weak: ManuallyDrop::new(weak),
+            registry_key: None,
+            internal: true,
+            task_span: None,
+        }
     }
 
     /// Returns a Future that gets fulfilled when cancellation (shutdown) is requested
@@ -65,8 +114,27 @@ impl ShutdownGuard {
     /// This method panics if the iternal mutex
     /// is poisoned while being used.
     #[inline]
-    pub async fn cancelled(&self) {
-        self.0.cancelled().await
+    pub fn cancelled(&self) -> Cancelled<'_> {
+        self.weak.cancelled()
+    }
+
+    /// Returns a Future that gets fulfilled when cancellation (shutdown) is requested
+    /// and the delay (if any) duration has been awaited.
+    ///
+    /// In contrast to [`Self::cancelled`] the returned future does not borrow the guard,
+    /// see [`CancelledOwned`] for more information.
+    ///
+    /// # Cancel safety
+    ///
+    /// This method is cancel safe.
+    ///
+    /// # Panics
+    ///
+    /// This method panics if the iternal mutex
+    /// is poisoned while being used.
+    #[inline]
+    pub fn cancelled_owned(&self) -> CancelledOwned {
+        self.weak.cancelled_owned()
     }
 
     /// Returns a Future that gets fulfilled when cancellation (shutdown) is requested.
@@ -88,8 +156,88 @@ impl ShutdownGuard {
     /// This method panics if the iternal mutex
     /// is poisoned while being used.
     #[inline]
-    pub async fn shutdown_signal_triggered(&self) {
-        self.0.shutdown_signal_triggered().await
+    pub fn shutdown_signal_triggered(&self) -> Cancelled<'_> {
+        self.weak.shutdown_signal_triggered()
+    }
+
+    /// Returns a Future that gets fulfilled when cancellation (shutdown) is requested.
+    ///
+    /// In contrast to [`Self::shutdown_signal_triggered`] the returned future
+    /// does not borrow the guard, see [`CancelledOwned`] for more information.
+    ///
+    /// # Cancel safety
+    ///
+    /// This method is cancel safe.
+    ///
+    /// # Panics
+    ///
+    /// This method panics if the iternal mutex
+    /// is poisoned while being used.
+    #[inline]
+    pub fn shutdown_signal_triggered_owned(&self) -> CancelledOwned {
+        self.weak.shutdown_signal_triggered_owned()
+    }
+
+    /// Runs the given future until it completes, resolving to `Some` of its output,
+    /// or until cancellation (shutdown) is requested and the delay (if any)
+    /// duration has been awaited, resolving to `None`.
+    ///
+    /// In case the future is cancelled, it is dropped without being polled to completion.
+    /// This is the equivalent of a `tokio::select!` between [`Self::cancelled`]
+    /// and the given future, biased towards the cancellation.
+    ///
+    /// ```
+    /// use tokio_graceful::Shutdown;
+    ///
+    /// # async fn example() {
+    /// let shutdown = Shutdown::no_signal();
+    /// let guard = shutdown.guard();
+    /// let output = guard.run_until_cancelled(async { 42 }).await;
+    /// // a shutdown without a signal is cancelled from the start
+    /// assert_eq!(output, None);
+    /// # }
+    /// ```
+    ///
+    /// # Cancel safety
+    ///
+    /// This method is as cancel safe as the given future.
+    #[inline]
+    pub fn run_until_cancelled<F: Future>(&self, future: F) -> UntilCancelled<F> {
+        self.weak.run_until_cancelled(future)
+    }
+
+    /// Runs the given future until it completes, resolving to `Some` of its output,
+    /// or until cancellation (shutdown) is requested, resolving to `None`.
+    ///
+    /// In contrast to [`Self::run_until_cancelled`] the delay (if any) is not awaited,
+    /// see [`Self::shutdown_signal_triggered`] for more information.
+    ///
+    /// # Cancel safety
+    ///
+    /// This method is as cancel safe as the given future.
+    #[inline]
+    pub fn run_until_shutdown_signal_triggered<F: Future>(&self, future: F) -> UntilCancelled<F> {
+        self.weak.run_until_shutdown_signal_triggered(future)
+    }
+
+    /// Returns whether cancellation (shutdown) has been requested
+    /// and the delay (if any) duration has been awaited.
+    ///
+    /// This is the non-blocking counterpart of [`Self::cancelled`],
+    /// useful for CPU-bound loops and `Drop` implementations.
+    #[inline]
+    pub fn is_cancelled(&self) -> bool {
+        self.weak.is_cancelled()
+    }
+
+    /// Returns whether cancellation (shutdown) has been requested,
+    /// without taking the delay (if any) into account.
+    ///
+    /// This is the non-blocking counterpart of [`Self::shutdown_signal_triggered`],
+    /// useful for CPU-bound loops and `Drop` implementations.
+    #[inline]
+    pub fn is_shutdown_signal_triggered(&self) -> bool {
+        self.weak.is_shutdown_signal_triggered()
     }
 
     /// Returns a [`crate::sync::JoinHandle`] that can be awaited on
@@ -100,12 +248,17 @@ impl ShutdownGuard {
         T: Future + Send + 'static,
         T::Output: Send + 'static,
     {
-        let guard = self.clone();
-        crate::sync::spawn(async move {
-            let output = task.await;
-            drop(guard);
-            output
-        })
+        #[cfg(feature = "metrics")]
+        crate::metrics::task_spawned();
+        let (guard, span) = self.clone().with_task_span();
+        spawn_task(
+            self.weak.task_tracker.as_ref(),
+            span.instrument(async move {
+                let output = task.await;
+                drop(guard);
+                output
+            }),
+        )
     }
 
     /// Returns a Tokio [`crate::sync::JoinHandle`] that can be awaited on
@@ -120,11 +273,31 @@ impl ShutdownGuard {
         T: Future + Send + 'static,
         T::Output: Send + 'static,
     {
-        crate::sync::spawn(async move {
-            let output = task.await;
-            drop(self);
-            output
-        })
+        #[cfg(feature = "metrics")]
+        crate::metrics::task_spawned();
+        self.into_spawn_internal_task(task)
+    }
+
+    /// Spawns the task in the same way as [`ShutdownGuard::into_spawn_task`],
+    /// but as a task held internally, e.g. by a child [`Shutdown`] on behalf of its parent,
+    /// which is not counted in the metrics.
+    ///
+    /// [`Shutdown`]: crate::Shutdown
+    pub(crate) fn into_spawn_internal_task<T>(self, task: T) -> JoinHandle<T::Output>
+    where
+        T: Future + Send + 'static,
+        T::Output: Send + 'static,
+    {
+        let task_tracker = self.weak.task_tracker.clone();
+        let (guard, span) = self.with_task_span();
+        spawn_task(
+            task_tracker.as_ref(),
+            span.instrument(async move {
+                let output = task.await;
+                drop(guard);
+                output
+            }),
+        )
     }
 
     /// Returns a Tokio [`crate::sync::JoinHandle`] that can be awaited on
@@ -136,8 +309,13 @@ impl ShutdownGuard {
         T: Future + Send + 'static,
         T::Output: Send + 'static,
     {
-        let guard = self.clone();
-        crate::sync::spawn(async move { task(guard).await })
+        #[cfg(feature = "metrics")]
+        crate::metrics::task_spawned();
+        let (guard, span) = self.clone().with_task_span();
+        spawn_task(
+            self.weak.task_tracker.as_ref(),
+            span.instrument(async move { task(guard).await }),
+        )
     }
 
     /// Returns a Tokio [`crate::sync::JoinHandle`] that can be awaited on
@@ -153,7 +331,331 @@ impl ShutdownGuard {
         T: Future + Send + 'static,
         T::Output: Send + 'static,
     {
-        crate::sync::spawn(async move { task(self).await })
+        #[cfg(feature = "metrics")]
+        crate::metrics::task_spawned();
+        let task_tracker = self.weak.task_tracker.clone();
+        let (guard, span) = self.with_task_span();
+        spawn_task(
+            task_tracker.as_ref(),
+            span.instrument(async move { task(guard).await }),
+        )
+    }
+
+    /// Returns a Tokio [`crate::sync::JoinHandle`] that can be awaited on
+    /// to wait for the spawned task to complete. See
+    /// [`crate::sync::spawn`] for more information.
+    ///
+    /// In contrast to [`ShutdownGuard::spawn_task`] the guard linked
+    /// to the task is named, see [`ShutdownGuard::named`] for more information.
+    #[track_caller]
+    pub fn spawn_task_named<T>(
+        &self,
+        name: impl Into<Cow<'static, str>>,
+        task: T,
+    ) -> JoinHandle<T::Output>
+    where
+        T: Future + Send + 'static,
+        T::Output: Send + 'static,
+    {
+        self.clone().named(name).into_spawn_task(task)
+    }
+
+    /// Returns a Tokio [`crate::sync::JoinHandle`] that can be awaited on
+    /// to wait for the spawned task (fn) to complete. See
+    /// [`crate::sync::spawn`] for more information.
+    ///
+    /// In contrast to [`ShutdownGuard::spawn_task_fn`] the guard linked
+    /// to the task is named, see [`ShutdownGuard::named`] for more information.
+    #[track_caller]
+    pub fn spawn_task_fn_named<F, T>(
+        &self,
+        name: impl Into<Cow<'static, str>>,
+        task: F,
+    ) -> JoinHandle<T::Output>
+    where
+        F: FnOnce(ShutdownGuard) -> T + Send + 'static,
+        T: Future + Send + 'static,
+        T::Output: Send + 'static,
+    {
+        self.clone().named(name).into_spawn_task_fn(task)
+    }
+
+    /// Returns a Tokio [`crate::sync::JoinHandle`] that can be awaited on
+    /// to wait for the spawned critical task to complete. See
+    /// [`crate::sync::spawn`] for more information.
+    ///
+    /// In contrast to [`ShutdownGuard::spawn_task`] the shutdown of the
+    /// linked [`Shutdown`] is triggered once the task returns, panics or is aborted,
+    /// recording the [`CriticalTaskExit`] as the [`ShutdownReason`].
+    ///
+    /// [`Shutdown`]: crate::Shutdown
+    /// [`CriticalTaskExit`]: crate::CriticalTaskExit
+    pub fn spawn_critical_task<T>(&self, task: T) -> JoinHandle<T::Output>
+    where
+        T: Future + Send + 'static,
+        T::Output: Send + 'static,
+    {
+        self.spawn_task(CriticalTask::new(task, self.shutdown_trigger()))
+    }
+
+    /// Returns a Tokio [`crate::sync::JoinHandle`] that can be awaited on
+    /// to wait for the spawned critical task (fn) to complete. See
+    /// [`crate::sync::spawn`] for more information.
+    ///
+    /// In contrast to [`ShutdownGuard::spawn_task_fn`] the shutdown of the
+    /// linked [`Shutdown`] is triggered once the task returns, panics or is aborted,
+    /// recording the [`CriticalTaskExit`] as the [`ShutdownReason`].
+    ///
+    /// [`Shutdown`]: crate::Shutdown
+    /// [`CriticalTaskExit`]: crate::CriticalTaskExit
+    pub fn spawn_critical_task_fn<F, T>(&self, task: F) -> JoinHandle<T::Output>
+    where
+        F: FnOnce(ShutdownGuard) -> T + Send + 'static,
+        T: Future + Send + 'static,
+        T::Output: Send + 'static,
+    {
+        #[cfg(feature = "metrics")]
+        crate::metrics::task_spawned();
+        let (guard, span) = self.clone().with_task_span();
+        spawn_task(
+            self.weak.task_tracker.as_ref(),
+            span.instrument(CriticalTask::new(
+                async move { task(guard).await },
+                self.shutdown_trigger(),
+            )),
+        )
+    }
+
+    /// Returns a Tokio [`crate::sync::JoinHandle`] that can be awaited on
+    /// to wait for the spawned fallible critical task to complete. See
+    /// [`crate::sync::spawn`] for more information.
+    ///
+    /// In contrast to [`ShutdownGuard::spawn_critical_task`] the error
+    /// returned by the task (if any) is recorded as [`CriticalTaskExit::Errored`].
+    ///
+    /// [`CriticalTaskExit::Errored`]: crate::CriticalTaskExit::Errored
+    pub fn spawn_fallible_critical_task<T, O, E>(&self, task: T) -> JoinHandle<T::Output>
+    where
+        T: Future<Output = Result<O, E>> + Send + 'static,
+        O: Send + 'static,
+        E: fmt::Display + Send + 'static,
+    {
+        self.spawn_task(CriticalTask::fallible(task, self.shutdown_trigger()))
+    }
+
+    /// Returns a Tokio [`crate::sync::JoinHandle`] that can be awaited on
+    /// to wait for the spawned fallible critical task (fn) to complete. See
+    /// [`crate::sync::spawn`] for more information.
+    ///
+    /// In contrast to [`ShutdownGuard::spawn_critical_task_fn`] the error
+    /// returned by the task (if any) is recorded as [`CriticalTaskExit::Errored`].
+    ///
+    /// [`CriticalTaskExit::Errored`]: crate::CriticalTaskExit::Errored
+    pub fn spawn_fallible_critical_task_fn<F, T, O, E>(&self, task: F) -> JoinHandle<T::Output>
+    where
+        F: FnOnce(ShutdownGuard) -> T + Send + 'static,
+        T: Future<Output = Result<O, E>> + Send + 'static,
+        O: Send + 'static,
+        E: fmt::Display + Send + 'static,
+    {
+        #[cfg(feature = "metrics")]
+        crate::metrics::task_spawned();
+        let (guard, span) = self.clone().with_task_span();
+        spawn_task(
+            self.weak.task_tracker.as_ref(),
+            span.instrument(CriticalTask::fallible(
+                async move { task(guard).await },
+                self.shutdown_trigger(),
+            )),
+        )
+    }
+
+    /// Returns a [`ShutdownTrigger`] which can be used to manually
+    /// trigger the shutdown of the [`Shutdown`] linked to this guard.
+    ///
+    /// [`Shutdown`]: crate::Shutdown
+    #[inline]
+    pub fn shutdown_trigger(&self) -> ShutdownTrigger {
+        self.weak.shutdown_trigger()
+    }
+
+    /// Returns the reason why the shutdown was triggered,
+    /// or `None` if the shutdown has not been triggered (yet).
+    ///
+    /// # Panics
+    ///
+    /// This method can panic if the internal mutex is poisoned.
+    #[inline]
+    pub fn shutdown_reason(&self) -> Option<ShutdownReason> {
+        self.weak.shutdown_reason()
+    }
+
+    /// Returns the [`Readiness`] of the [`Shutdown`] linked to this guard.
+    ///
+    /// [`Shutdown`]: crate::Shutdown
+    #[inline]
+    pub fn readiness(&self) -> Readiness {
+        self.weak.readiness()
+    }
+
+    /// Returns the current [`Lifecycle`] state of the [`Shutdown`] linked to this guard.
+    ///
+    /// [`Shutdown`]: crate::Shutdown
+    #[inline]
+    pub fn lifecycle(&self) -> Lifecycle {
+        self.weak.lifecycle()
+    }
+
+    /// Returns a [`watch::Receiver`] which can be used to (asynchronously)
+    /// watch the [`Lifecycle`] of the [`Shutdown`] linked to this guard for changes.
+    ///
+    /// [`Shutdown`]: crate::Shutdown
+    /// [`watch::Receiver`]: tokio::sync::watch::Receiver
+    #[inline]
+    pub fn watch_lifecycle(&self) -> tokio::sync::watch::Receiver<Lifecycle> {
+        self.weak.watch_lifecycle()
+    }
+
+    /// Returns a [`ReloadTrigger`] which can be used to request a reload
+    /// from all [`ReloadGuard`]s linked to the [`Shutdown`] of this guard.
+    ///
+    /// [`Shutdown`]: crate::Shutdown
+    #[inline]
+    pub fn reload_trigger(&self) -> ReloadTrigger {
+        self.weak.reload_trigger()
+    }
+
+    /// Returns a [`ReloadGuard`] which can be used to wait for reloads
+    /// to be requested, until the [`Shutdown`] of this guard is cancelled.
+    ///
+    /// [`Shutdown`]: crate::Shutdown
+    #[inline]
+    pub fn reload_guard(&self) -> ReloadGuard {
+        self.weak.reload_guard()
+    }
+
+    /// Creates a new guard from this guard, held internally by a [`Shutdown`] or one of its phases,
+    /// to be handed out to the user, such that it is tracked for leaks as created rather than cloned.
+    ///
+    /// [`Shutdown`]: crate::Shutdown
+    #[track_caller]
+    pub(crate) fn create(&self) -> Self {
+        self.clone_from_origin("created")
+    }
+
+    /// Clones the guard, tracking the clone for leaks with the given origin
+    /// (see [`ShutdownBuilder::with_leak_detection`]).
+    ///
+    /// [`ShutdownBuilder::with_leak_detection`]: crate::ShutdownBuilder::with_leak_detection
+    #[track_caller]
+    #[cfg_attr(not(feature = "leak-detection"), allow(unused_variables))]
+    fn clone_from_origin(&self, origin: &'static str) -> Self {
+        let value = &self.weak.ref_count.fetch_add(1, Ordering::SeqCst);
+        tracing::trace!("clone shutdown guard: ref_count+1: {}", value + 1);
+        #[cfg(feature = "metrics")]
+        crate::metrics::guard_created(self.weak.registry.scope());
+        let registry_key = self
+            .registry_key
+            .and_then(|key| self.weak.registry.name(key))
+            .map(|name| self.weak.registry.insert(name, Location::caller()));
+        Self {
+            weak: self.weak.clone(),
+            registry_key,
+            #[cfg(feature = "leak-detection")]
+            leak_key: self.weak.track_leak(origin),
+            internal: false,
+            task_span: None,
+        }
+    }
+
+    /// Attaches a new [`TaskSpan`] to this guard, which is about to be handed to a task,
+    /// returning the span with which the task is to be instrumented.
+    fn with_task_span(mut self) -> (Self, TaskSpan) {
+        let span = TaskSpan::new(&self);
+        self.task_span = Some(span.clone());
+        (self, span)
+    }
+
+    pub(crate) fn weak(&self) -> &WeakShutdownGuard {
+        &self.weak
+    }
+
+    pub(crate) fn registry(&self) -> &Registry {
+        &self.weak.registry
+    }
+
+    /// Returns the name of the guard, if it is named.
+    pub(crate) fn name(&self) -> Option<Cow<'static, str>> {
+        self.registry_key
+            .and_then(|key| self.weak.registry.name(key))
+    }
+
+    /// Names the guard, registering it such that it can be identified
+    /// using [`Shutdown::live_guards`] as long as it is alive,
+    /// together with the location it was named at and its age.
+    ///
+    /// Clones of a named guard are registered under the same name.
+    /// Naming an already named guard replaces its name.
+    ///
+    /// In contrast to the creation of regular guards,
+    /// the creation of named guards is not lock-free.
+    ///
+    /// # Panics
+    ///
+    /// This method panics if the internal mutex is poisoned.
+    ///
+    /// [`Shutdown::live_guards`]: crate::Shutdown::live_guards
+    #[track_caller]
+    pub fn named(mut self, name: impl Into<Cow<'static, str>>) -> Self {
+        if let Some(key) = self.registry_key.take() {
+            self.weak.registry.remove(key);
+        }
+        self.registry_key = Some(self.weak.registry.insert(name.into(), Location::caller()));
+        self
+    }
+
+    /// Returns a child [`Shutdown`], linked to the [`Shutdown`] of this guard.
+    ///
+    /// The child can be shutdown and awaited on its own, but is also cancelled
+    /// automatically when its parent is cancelled. As long as the child
+    /// has [`ShutdownGuard`]s alive, the parent is prevented from shutting down.
+    ///
+    /// Use [`ShutdownGuard::child_with_signal`] to be able to shutdown
+    /// the child without cancelling its parent.
+    ///
+    /// The child inherits the reloads of its parent, and whether the parent
+    /// aborts its tasks on timeout (see [`ShutdownBuilder::with_abort_on_timeout`]),
+    /// records a timeline (see [`ShutdownBuilder::with_timeline`]) and detects
+    /// leaked guards. The delay, phases, drain progress, [`Readiness`]
+    /// and systemd notifications of the parent are not inherited, as these
+    /// concern the parent itself. Use [`ShutdownBuilder::with_parent`]
+    /// to configure the child yourself instead.
+    ///
+    /// The guards of the child are reported (e.g. in the metrics and leak detection)
+    /// under their own scope, nested within the scope of this guard and named after
+    /// this guard if it is named (e.g. `main/worker`), or numbered otherwise (e.g. `main/child-1`).
+    ///
+    /// [`Shutdown`]: crate::Shutdown
+    /// [`ShutdownBuilder::with_abort_on_timeout`]: crate::ShutdownBuilder::with_abort_on_timeout
+    /// [`ShutdownBuilder::with_timeline`]: crate::ShutdownBuilder::with_timeline
+    /// [`ShutdownBuilder::with_parent`]: crate::ShutdownBuilder::with_parent
+    pub fn child(&self) -> Shutdown {
+        Shutdown::new_child(self, std::future::pending::<()>())
+    }
+
+    /// Returns a child [`Shutdown`], linked to the [`Shutdown`] of this guard,
+    /// which is cancelled either when the given signal is triggered
+    /// or when its parent is cancelled.
+    ///
+    /// As long as the child has [`ShutdownGuard`]s alive,
+    /// the parent is prevented from shutting down.
+    ///
+    /// See [`ShutdownGuard::child`] for more information,
+    /// including the options inherited from the parent.
+    ///
+    /// [`Shutdown`]: crate::Shutdown
+    pub fn child_with_signal(&self, signal: impl Future + Send + 'static) -> Shutdown {
+        Shutdown::new_child(self, signal)
     }
 
     /// Downgrades the guard to a [`WeakShutdownGuard`],
@@ -162,7 +664,28 @@ impl ShutdownGuard {
     ///
     /// [`Shutdown::shutdown`]: crate::Shutdown::shutdown
     pub fn downgrade(mut self) -> WeakShutdownGuard {
-        unsafe { ManuallyDrop::take(&mut self.0) }
+        unsafe { ManuallyDrop::take(&mut self.weak) }
+    }
+
+    fn from_weak(weak_guard: WeakShutdownGuard, internal: bool) -> Self {
+        let value = weak_guard.ref_count.fetch_add(1, Ordering::SeqCst);
+        tracing::trace!("from weak shutdown guard: ref_count+1: {}", value + 1);
+        #[cfg(feature = "metrics")]
+        if !internal {
+            crate::metrics::guard_created(weak_guard.registry.scope());
+        }
+        Self {
+            #[cfg(feature = "leak-detection")]
+            leak_key: if internal {
+                None
+            } else {
+                weak_guard.track_leak("upgraded")
+            },
+            weak: ManuallyDrop::new(weak_guard),
+            registry_key: None,
+            internal,
+            task_span: None,
+        }
     }
 
     /// Clones the guard as a [`WeakShutdownGuard`],
@@ -171,51 +694,102 @@ impl ShutdownGuard {
     ///
     /// [`Shutdown::shutdown`]: crate::Shutdown::shutdown
     pub fn clone_weak(&self) -> WeakShutdownGuard {
-        ManuallyDrop::into_inner(self.0.clone())
+        ManuallyDrop::into_inner(self.weak.clone())
     }
 }
 
 impl Clone for ShutdownGuard {
+    /// Clones the guard, registering the clone
+    /// under the same name in case this guard is named.
+    #[track_caller]
     fn clone(&self) -> Self {
-        let value = &self.0.ref_count.fetch_add(1, Ordering::SeqCst);
-        tracing::trace!("clone shutdown guard: ref_count+1: {}", value + 1);
-        Self(self.0.clone())
+        self.clone_from_origin("cloned")
     }
 }
 
 impl From<WeakShutdownGuard> for ShutdownGuard {
     fn from(weak_guard: WeakShutdownGuard) -> ShutdownGuard {
-        let value = weak_guard.ref_count.fetch_add(1, Ordering::SeqCst);
-        tracing::trace!("from weak shutdown guard: ref_count+1: {}", value + 1);
-        Self(ManuallyDrop::new(weak_guard))
+        ShutdownGuard::from_weak(weak_guard, false)
     }
 }
 
 impl Drop for ShutdownGuard {
     fn drop(&mut self) {
-        let cnt = self.0.ref_count.fetch_sub(1, Ordering::SeqCst);
-        tracing::trace!("drop shutdown guard: ref_count-1: {}", cnt - 1);
-        if cnt == 1 {
-            self.0.zero_tx.trigger();
+        if let Some(task_span) = self.task_span.take() {
+            task_span.released();
+        }
+        let name = self
+            .registry_key
+            .take()
+            .and_then(|key| self.weak.registry.remove(key));
+        #[cfg(feature = "leak-detection")]
+        if let (Some(key), Some(leak_detector)) = (
+            self.leak_key.take(),
+            self.weak.shutdown_trigger.leak_detector(),
+        ) {
+            leak_detector.remove(key);
+        }
+        // the bookkeeping is done prior to notifying the drain of the last guard,
+        // such that it is complete once the drain (and its report) continues
+        let release = || self.weak.ref_count.fetch_sub(1, Ordering::SeqCst) - 1;
+        let remaining = match self.weak.shutdown_trigger.timeline() {
+            Some(timeline) => {
+                timeline.record_guard_released(self.weak.registry.scope(), name, release)
+            }
+            None => release(),
+        };
+        tracing::trace!("drop shutdown guard: ref_count-1: {}", remaining);
+        #[cfg(feature = "metrics")]
+        if !self.internal {
+            crate::metrics::guard_dropped(self.weak.registry.scope());
+        }
+        if self.weak.trigger_rx.is_triggered() {
+            self.weak
+                .shutdown_trigger
+                .lifecycle()
+                .guard_dropped(&self.weak.ref_count);
+        }
+        if remaining == 0 {
+            self.weak.zero_tx.trigger();
         }
     }
 }
 
 impl WeakShutdownGuard {
+    #[allow(clippy::too_many_arguments)]
     pub(crate) fn new(
         trigger_rx: Receiver,
         shutdown_signal_trigger_rx: Option<Receiver>,
         zero_tx: Sender,
         ref_count: Arc<AtomicUsize>,
+        shutdown_trigger: ShutdownTrigger,
+        registry: Registry,
+        task_tracker: Option<TaskTracker>,
+        reload_trigger: ReloadTrigger,
     ) -> Self {
         Self {
             trigger_rx,
             shutdown_signal_trigger_rx,
             zero_tx,
             ref_count,
+            shutdown_trigger,
+            registry,
+            task_tracker,
+            reload_trigger,
         }
     }
 
+    /// Tracks a (strong) guard created from this guard in case leak detection is enabled,
+    /// see [`ShutdownBuilder::with_leak_detection`] for more information.
+    ///
+    /// [`ShutdownBuilder::with_leak_detection`]: crate::ShutdownBuilder::with_leak_detection
+    #[cfg(feature = "leak-detection")]
+    fn track_leak(&self, origin: &'static str) -> Option<usize> {
+        self.shutdown_trigger
+            .leak_detector()
+            .map(|leak_detector| leak_detector.insert(self.registry.scope(), origin))
+    }
+
     /// Returns a Future that gets fulfilled when cancellation (shutdown) is requested
     /// and the delay (buffer) duration has been awaited on.
     ///
@@ -234,8 +808,27 @@ impl WeakShutdownGuard {
     /// This method panics if the iternal mutex
     /// is poisoned while being used.
     #[inline]
-    pub async fn cancelled(&self) {
-        self.trigger_rx.clone().await;
+    pub fn cancelled(&self) -> Cancelled<'_> {
+        Cancelled::new(self.trigger_rx.clone())
+    }
+
+    /// Returns a Future that gets fulfilled when cancellation (shutdown) is requested
+    /// and the delay (buffer) duration has been awaited on.
+    ///
+    /// In contrast to [`Self::cancelled`] the returned future does not borrow the guard,
+    /// see [`CancelledOwned`] for more information.
+    ///
+    /// # Cancel safety
+    ///
+    /// This method is cancel safe.
+    ///
+    /// # Panics
+    ///
+    /// This method panics if the iternal mutex
+    /// is poisoned while being used.
+    #[inline]
+    pub fn cancelled_owned(&self) -> CancelledOwned {
+        CancelledOwned::new(self.trigger_rx.clone())
     }
 
     /// Returns a Future that gets fulfilled when cancellation (shutdown) is requested
@@ -259,17 +852,90 @@ impl WeakShutdownGuard {
     /// This method panics if the iternal mutex
     /// is poisoned while being used.
     #[inline]
-    pub async fn shutdown_signal_triggered(&self) {
+    pub fn shutdown_signal_triggered(&self) -> Cancelled<'_> {
+        Cancelled::new(self.shutdown_signal_rx())
+    }
+
+    /// Returns a Future that gets fulfilled when cancellation (shutdown) is requested
+    /// without awaiting the delay duration first, if one is set.
+    ///
+    /// In contrast to [`Self::shutdown_signal_triggered`] the returned future
+    /// does not borrow the guard, see [`CancelledOwned`] for more information.
+    ///
+    /// # Cancel safety
+    ///
+    /// This method is cancel safe.
+    ///
+    /// # Panics
+    ///
+    /// This method panics if the iternal mutex
+    /// is poisoned while being used.
+    #[inline]
+    pub fn shutdown_signal_triggered_owned(&self) -> CancelledOwned {
+        CancelledOwned::new(self.shutdown_signal_rx())
+    }
+
+    pub(crate) fn shutdown_signal_rx(&self) -> Receiver {
         self.shutdown_signal_trigger_rx
             .clone()
             .unwrap_or_else(|| self.trigger_rx.clone())
-            .await
     }
 
-    /// Returns a Future that gets fulfilled when cancellation (shutdown) is requested.
+    /// Runs the given future until it completes, resolving to `Some` of its output,
+    /// or until cancellation (shutdown) is requested and the delay (if any)
+    /// duration has been awaited, resolving to `None`.
+    ///
+    /// See [`ShutdownGuard::run_until_cancelled`] for more information.
+    ///
+    /// # Cancel safety
     ///
-    /// In contrast to [`ShutdownGuard::cancelled`] this method consumes the guard,
-    /// ensuring the guard is dropped once the future is fulfilled.
+    /// This method is as cancel safe as the given future.
+    #[inline]
+    pub fn run_until_cancelled<F: Future>(&self, future: F) -> UntilCancelled<F> {
+        UntilCancelled::new(future, self.cancelled_owned())
+    }
+
+    /// Runs the given future until it completes, resolving to `Some` of its output,
+    /// or until cancellation (shutdown) is requested, resolving to `None`.
+    ///
+    /// See [`ShutdownGuard::run_until_shutdown_signal_triggered`] for more information.
+    ///
+    /// # Cancel safety
+    ///
+    /// This method is as cancel safe as the given future.
+    #[inline]
+    pub fn run_until_shutdown_signal_triggered<F: Future>(&self, future: F) -> UntilCancelled<F> {
+        UntilCancelled::new(future, self.shutdown_signal_triggered_owned())
+    }
+
+    /// Returns whether cancellation (shutdown) has been requested
+    /// and the delay (if any) duration has been awaited.
+    ///
+    /// This is the non-blocking counterpart of [`Self::cancelled`],
+    /// useful for CPU-bound loops and `Drop` implementations.
+    #[inline]
+    pub fn is_cancelled(&self) -> bool {
+        self.trigger_rx.is_triggered()
+    }
+
+    /// Returns whether cancellation (shutdown) has been requested,
+    /// without taking the delay (if any) into account.
+    ///
+    /// This is the non-blocking counterpart of [`Self::shutdown_signal_triggered`],
+    /// useful for CPU-bound loops and `Drop` implementations.
+    #[inline]
+    pub fn is_shutdown_signal_triggered(&self) -> bool {
+        self.shutdown_signal_trigger_rx
+            .as_ref()
+            .unwrap_or(&self.trigger_rx)
+            .is_triggered()
+    }
+
+    /// Returns a Future that gets fulfilled when cancellation (shutdown) is requested
+    /// and the delay (buffer) duration has been awaited on.
+    ///
+    /// In contrast to [`WeakShutdownGuard::cancelled`] this method consumes the guard,
+    /// returning a future which does not borrow it, see [`CancelledOwned`] for more information.
     ///
     /// The future will complete immediately if the token is already cancelled when this method is called.
     ///
@@ -282,8 +948,72 @@ impl WeakShutdownGuard {
     /// This method panics if the iternal mutex
     /// is poisoned while being used.
     #[inline]
-    pub async fn into_cancelled(self) {
-        self.cancelled().await;
+    pub fn into_cancelled(self) -> CancelledOwned {
+        self.cancelled_owned()
+    }
+
+    /// Returns a [`ShutdownTrigger`] which can be used to manually
+    /// trigger the shutdown of the [`Shutdown`] linked to this guard.
+    ///
+    /// [`Shutdown`]: crate::Shutdown
+    #[inline]
+    pub fn shutdown_trigger(&self) -> ShutdownTrigger {
+        self.shutdown_trigger.clone()
+    }
+
+    /// Returns the reason why the shutdown was triggered,
+    /// or `None` if the shutdown has not been triggered (yet).
+    ///
+    /// # Panics
+    ///
+    /// This method can panic if the internal mutex is poisoned.
+    #[inline]
+    pub fn shutdown_reason(&self) -> Option<ShutdownReason> {
+        self.shutdown_trigger.reason()
+    }
+
+    /// Returns the [`Readiness`] of the [`Shutdown`] linked to this guard.
+    ///
+    /// [`Shutdown`]: crate::Shutdown
+    #[inline]
+    pub fn readiness(&self) -> Readiness {
+        self.shutdown_trigger.readiness()
+    }
+
+    /// Returns the current [`Lifecycle`] state of the [`Shutdown`] linked to this guard.
+    ///
+    /// [`Shutdown`]: crate::Shutdown
+    #[inline]
+    pub fn lifecycle(&self) -> Lifecycle {
+        self.shutdown_trigger.lifecycle().current()
+    }
+
+    /// Returns a [`watch::Receiver`] which can be used to (asynchronously)
+    /// watch the [`Lifecycle`] of the [`Shutdown`] linked to this guard for changes.
+    ///
+    /// [`Shutdown`]: crate::Shutdown
+    /// [`watch::Receiver`]: tokio::sync::watch::Receiver
+    #[inline]
+    pub fn watch_lifecycle(&self) -> tokio::sync::watch::Receiver<Lifecycle> {
+        self.shutdown_trigger.lifecycle().subscribe()
+    }
+
+    /// Returns a [`ReloadTrigger`] which can be used to request a reload
+    /// from all [`ReloadGuard`]s linked to the [`Shutdown`] of this guard.
+    ///
+    /// [`Shutdown`]: crate::Shutdown
+    #[inline]
+    pub fn reload_trigger(&self) -> ReloadTrigger {
+        self.reload_trigger.clone()
+    }
+
+    /// Returns a [`ReloadGuard`] which can be used to wait for reloads
+    /// to be requested, until the [`Shutdown`] of this guard is cancelled.
+    ///
+    /// [`Shutdown`]: crate::Shutdown
+    #[inline]
+    pub fn reload_guard(&self) -> ReloadGuard {
+        ReloadGuard::new(self.clone())
     }
 
     /// Upgrades the weak guard to a [`ShutdownGuard`],
@@ -295,4 +1025,13 @@ impl WeakShutdownGuard {
     pub fn upgrade(self) -> ShutdownGuard {
         self.into()
     }
+
+    /// Upgrades the weak guard to a [`ShutdownGuard`] held internally,
+    /// e.g. by a child [`Shutdown`] on behalf of its parent,
+    /// which is neither counted in the metrics nor tracked for leaks.
+    ///
+    /// [`Shutdown`]: crate::Shutdown
+    pub(crate) fn upgrade_internal(self) -> ShutdownGuard {
+        ShutdownGuard::from_weak(self, true)
+    }
 }
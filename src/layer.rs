@@ -0,0 +1,382 @@
+//! [`tower`] middleware, linking each in-flight request to a [`ShutdownGuard`]
+//! and rejecting new requests once the shutdown reached a configured stage.
+//!
+//! [`tower`]: https://docs.rs/tower
+
+use std::{
+    future::Future,
+    pin::Pin,
+    task::{ready, Context, Poll},
+    time::Duration,
+};
+
+use pin_project_lite::pin_project;
+use tower_layer::Layer;
+use tower_service::Service;
+
+use crate::{
+    sync::{Arc, AtomicUsize, Ordering},
+    ShutdownGuard, WeakShutdownGuard,
+};
+
+/// The stage of the shutdown from which a [`GracefulService`] rejects new requests.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[non_exhaustive]
+pub enum RejectStage {
+    /// Reject new requests once the shutdown signal is triggered,
+    /// prior to the delay (if any) being awaited.
+    ShutdownSignal,
+    /// Reject new requests once the shutdown is cancelled,
+    /// after the delay (if any) has been awaited.
+    #[default]
+    Cancelled,
+}
+
+/// Creates the response returned by a [`GracefulService`]
+/// for the requests it rejects during a shutdown.
+///
+/// Implemented for [`ServiceUnavailable`] and for any `Fn(Request) -> Response`.
+pub trait Rejection<Request, Response> {
+    /// Returns the response for the given rejected request.
+    fn reject(&self, request: Request) -> Response;
+}
+
+impl<F, Request, Response> Rejection<Request, Response> for F
+where
+    F: Fn(Request) -> Response,
+{
+    fn reject(&self, request: Request) -> Response {
+        self(request)
+    }
+}
+
+/// The default [`Rejection`] of a [`GracefulLayer`],
+/// responding to rejected HTTP requests with a `503 Service Unavailable`,
+/// optionally with a `Retry-After` header.
+#[derive(Debug, Clone, Default)]
+pub struct ServiceUnavailable {
+    retry_after: Option<Duration>,
+}
+
+impl ServiceUnavailable {
+    /// Creates a new [`ServiceUnavailable`] rejection, without a `Retry-After` header.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds a `Retry-After` header to the rejection responses,
+    /// with the given duration in (whole) seconds.
+    pub fn with_retry_after(mut self, retry_after: Duration) -> Self {
+        self.retry_after = Some(retry_after);
+        self
+    }
+}
+
+impl<ReqBody, ResBody: Default> Rejection<http::Request<ReqBody>, http::Response<ResBody>>
+    for ServiceUnavailable
+{
+    fn reject(&self, _request: http::Request<ReqBody>) -> http::Response<ResBody> {
+        let mut response = http::Response::new(ResBody::default());
+        *response.status_mut() = http::StatusCode::SERVICE_UNAVAILABLE;
+        if let Some(retry_after) = self.retry_after {
+            response
+                .headers_mut()
+                .insert(http::header::RETRY_AFTER, retry_after.as_secs().into());
+        }
+        response
+    }
+}
+
+/// Counts the requests in-flight in a [`GracefulService`],
+/// obtained using [`GracefulLayer::in_flight_requests`].
+///
+/// It can be cloned cheaply and is shared by all services created by the same layer.
+#[derive(Debug, Clone, Default)]
+pub struct InFlightRequests {
+    count: Arc<AtomicUsize>,
+}
+
+impl InFlightRequests {
+    /// Returns the amount of requests which are in-flight,
+    /// meaning their response future has not resolved yet.
+    pub fn count(&self) -> usize {
+        self.count.load(Ordering::SeqCst)
+    }
+}
+
+/// A [`Layer`] which wraps services in a [`GracefulService`],
+/// linking each in-flight request to a [`ShutdownGuard`] such that the
+/// [`Shutdown`] waits for them to complete, and rejecting new requests
+/// once the shutdown reached the configured [`RejectStage`].
+///
+/// The layer and its services do not hold a [`ShutdownGuard`] themselves,
+/// and thus do not prevent the [`Shutdown::shutdown`] future from completing
+/// when no requests are in-flight.
+///
+/// A request is in-flight until its response future resolves, which for an HTTP service
+/// is once the response headers are available: the writing of the response body,
+/// e.g. a streamed body, is not covered. A handler streaming its response body can keep
+/// a [`ShutdownGuard`] alive within the body, such that the shutdown waits for it as well.
+///
+/// ```
+/// use std::time::Duration;
+///
+/// use tokio_graceful::{GracefulLayer, RejectStage, Shutdown, ServiceUnavailable};
+///
+/// let shutdown = Shutdown::no_signal();
+/// let layer = GracefulLayer::new(&shutdown.guard_weak())
+///     .reject_at(RejectStage::ShutdownSignal)
+///     .with_rejection(ServiceUnavailable::new().with_retry_after(Duration::from_secs(5)));
+/// let in_flight_requests = layer.in_flight_requests();
+/// assert_eq!(in_flight_requests.count(), 0);
+/// ```
+///
+/// [`Shutdown`]: crate::Shutdown
+/// [`Shutdown::shutdown`]: crate::Shutdown::shutdown
+#[derive(Debug, Clone)]
+pub struct GracefulLayer<R = ServiceUnavailable> {
+    guard: WeakShutdownGuard,
+    stage: RejectStage,
+    rejection: R,
+    in_flight: InFlightRequests,
+}
+
+impl GracefulLayer {
+    /// Creates a new [`GracefulLayer`] for the [`Shutdown`] of the given guard,
+    /// rejecting new requests with a [`ServiceUnavailable`] response
+    /// once the shutdown is cancelled.
+    ///
+    /// [`Shutdown`]: crate::Shutdown
+    pub fn new<G: AsRef<WeakShutdownGuard>>(guard: &G) -> Self {
+        Self {
+            guard: guard.as_ref().clone(),
+            stage: RejectStage::default(),
+            rejection: ServiceUnavailable::default(),
+            in_flight: InFlightRequests::default(),
+        }
+    }
+}
+
+impl<R> GracefulLayer<R> {
+    /// Sets the stage of the shutdown from which new requests are rejected.
+    pub fn reject_at(mut self, stage: RejectStage) -> Self {
+        self.stage = stage;
+        self
+    }
+
+    /// Sets the [`Rejection`] used to respond to rejected requests.
+    pub fn with_rejection<T>(self, rejection: T) -> GracefulLayer<T> {
+        GracefulLayer {
+            guard: self.guard,
+            stage: self.stage,
+            rejection,
+            in_flight: self.in_flight,
+        }
+    }
+
+    /// Returns the [`InFlightRequests`] of the services created by this layer.
+    pub fn in_flight_requests(&self) -> InFlightRequests {
+        self.in_flight.clone()
+    }
+}
+
+impl<S, R: Clone> Layer<S> for GracefulLayer<R> {
+    type Service = GracefulService<S, R>;
+
+    fn layer(&self, inner: S) -> Self::Service {
+        GracefulService {
+            inner,
+            ready: ReadyState::NotReady,
+            guard: self.guard.clone(),
+            stage: self.stage,
+            rejection: self.rejection.clone(),
+            in_flight: self.in_flight.clone(),
+        }
+    }
+}
+
+/// A [`Service`] linking each in-flight request to a [`ShutdownGuard`],
+/// rejecting new requests once the shutdown reached the configured [`RejectStage`].
+///
+/// Whether a request is rejected is decided by [`Service::poll_ready`],
+/// in which case the wrapped service is not polled for readiness,
+/// such that no capacity (e.g. a buffer slot or concurrency permit)
+/// is reserved for a request which is never passed on to it.
+///
+/// A request is in-flight until its response future resolves, not covering the
+/// writing of the response body, see [`GracefulLayer`] for more information.
+///
+/// Created by [`GracefulLayer`].
+#[derive(Debug)]
+pub struct GracefulService<S, R = ServiceUnavailable> {
+    inner: S,
+    ready: ReadyState,
+    guard: WeakShutdownGuard,
+    stage: RejectStage,
+    rejection: R,
+    in_flight: InFlightRequests,
+}
+
+/// The decision taken by [`Service::poll_ready`] of a [`GracefulService`],
+/// applied to the request passed to the next [`Service::call`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum ReadyState {
+    /// Not polled for readiness (since the last call).
+    NotReady,
+    /// The wrapped service is ready, and the request is to be passed on to it.
+    Inner,
+    /// The request is to be rejected.
+    Reject,
+}
+
+impl<S: Clone, R: Clone> Clone for GracefulService<S, R> {
+    fn clone(&self) -> Self {
+        // the readiness of a service is not shared with its clones
+        Self {
+            inner: self.inner.clone(),
+            ready: ReadyState::NotReady,
+            guard: self.guard.clone(),
+            stage: self.stage,
+            rejection: self.rejection.clone(),
+            in_flight: self.in_flight.clone(),
+        }
+    }
+}
+
+impl<S, R> GracefulService<S, R> {
+    /// Returns the [`InFlightRequests`] of this service.
+    pub fn in_flight_requests(&self) -> InFlightRequests {
+        self.in_flight.clone()
+    }
+
+    /// Returns a reference to the wrapped service.
+    pub fn get_ref(&self) -> &S {
+        &self.inner
+    }
+
+    /// Consumes this service, returning the wrapped service.
+    pub fn into_inner(self) -> S {
+        self.inner
+    }
+
+    fn rejecting(&self) -> bool {
+        match self.stage {
+            RejectStage::ShutdownSignal => self.guard.is_shutdown_signal_triggered(),
+            RejectStage::Cancelled => self.guard.is_cancelled(),
+        }
+    }
+}
+
+impl<S, R, Request> Service<Request> for GracefulService<S, R>
+where
+    S: Service<Request>,
+    R: Rejection<Request, S::Response>,
+{
+    type Response = S::Response;
+    type Error = S::Error;
+    type Future = GracefulFuture<S::Future, S::Response>;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        // once the wrapped service is ready, the request is passed on to it,
+        // as it might have reserved capacity for it
+        if self.ready != ReadyState::Inner && self.rejecting() {
+            self.ready = ReadyState::Reject;
+            return Poll::Ready(Ok(()));
+        }
+        let result = ready!(self.inner.poll_ready(cx));
+        if result.is_ok() {
+            self.ready = ReadyState::Inner;
+        }
+        Poll::Ready(result)
+    }
+
+    fn call(&mut self, request: Request) -> Self::Future {
+        let reject = match std::mem::replace(&mut self.ready, ReadyState::NotReady) {
+            ReadyState::Inner => false,
+            ReadyState::Reject => true,
+            // the wrapped service decides what happens when it is called without being ready
+            ReadyState::NotReady => self.rejecting(),
+        };
+        if reject {
+            tracing::trace!("::layer: shutdown: reject request");
+            return GracefulFuture {
+                kind: Kind::Rejected {
+                    response: Some(self.rejection.reject(request)),
+                },
+            };
+        }
+        let token = InFlightToken::new(&self.in_flight, self.guard.clone().upgrade());
+        GracefulFuture {
+            kind: Kind::InFlight {
+                future: self.inner.call(request),
+                _token: token,
+            },
+        }
+    }
+}
+
+pin_project! {
+    /// The response [`Future`] of a [`GracefulService`].
+    #[derive(Debug)]
+    #[must_use = "futures do nothing unless you `.await` or poll them"]
+    pub struct GracefulFuture<F, T> {
+        #[pin]
+        kind: Kind<F, T>,
+    }
+}
+
+pin_project! {
+    #[project = KindProj]
+    #[derive(Debug)]
+    enum Kind<F, T> {
+        InFlight {
+            #[pin]
+            future: F,
+            _token: InFlightToken,
+        },
+        Rejected {
+            response: Option<T>,
+        },
+    }
+}
+
+impl<F, T, E> Future for GracefulFuture<F, T>
+where
+    F: Future<Output = Result<T, E>>,
+{
+    type Output = Result<T, E>;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        match self.project().kind.project() {
+            KindProj::InFlight { future, .. } => future.poll(cx),
+            KindProj::Rejected { response } => {
+                Poll::Ready(Ok(response.take().expect("polled after completion")))
+            }
+        }
+    }
+}
+
+/// Keeps the [`ShutdownGuard`] of an in-flight request alive
+/// and counts it as in-flight until dropped.
+#[derive(Debug)]
+struct InFlightToken {
+    in_flight: InFlightRequests,
+    _guard: ShutdownGuard,
+}
+
+impl InFlightToken {
+    fn new(in_flight: &InFlightRequests, guard: ShutdownGuard) -> Self {
+        in_flight.count.fetch_add(1, Ordering::SeqCst);
+        Self {
+            in_flight: in_flight.clone(),
+            _guard: guard,
+        }
+    }
+}
+
+impl Drop for InFlightToken {
+    fn drop(&mut self) {
+        let count = self.in_flight.count.fetch_sub(1, Ordering::SeqCst) - 1;
+        tracing::trace!("::layer: request completed: {} in-flight request(s)", count);
+    }
+}
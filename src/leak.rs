@@ -0,0 +1,114 @@
+//! Detection of leaked [`ShutdownGuard`]s, e.g. guards which were `mem::forget`-ed
+//! or are stuck in a detached task, and thus prevent the shutdown from completing.
+//!
+//! [`ShutdownGuard`]: crate::ShutdownGuard
+
+use std::{backtrace::Backtrace, borrow::Cow, time::Duration};
+
+use slab::Slab;
+use tokio::task::AbortHandle;
+
+use crate::sync::{Arc, Mutex};
+
+/// Tracks the backtraces of all live [`ShutdownGuard`]s of a [`Shutdown`],
+/// such that the still-live guards can be reported once the waiting for them
+/// took longer than the configured threshold.
+///
+/// Each guard is tracked together with its scope, either "main", the name of a phase
+/// or the scope of a child, such that each drain (of the [`Shutdown`] or one of its phases)
+/// only reports the guards it is waiting for.
+///
+/// See [`ShutdownBuilder::with_leak_detection`] for more information.
+///
+/// [`ShutdownGuard`]: crate::ShutdownGuard
+/// [`Shutdown`]: crate::Shutdown
+/// [`ShutdownBuilder::with_leak_detection`]: crate::ShutdownBuilder::with_leak_detection
+#[derive(Debug, Clone)]
+pub(crate) struct LeakDetector {
+    threshold: Duration,
+    guards: Arc<Mutex<Slab<LiveGuard>>>,
+}
+
+#[derive(Debug)]
+struct LiveGuard {
+    scope: Cow<'static, str>,
+    origin: &'static str,
+    backtrace: Backtrace,
+}
+
+impl LeakDetector {
+    pub(crate) fn new(threshold: Duration) -> Self {
+        Self {
+            threshold,
+            guards: Default::default(),
+        }
+    }
+
+    pub(crate) fn threshold(&self) -> Duration {
+        self.threshold
+    }
+
+    /// Tracks a new guard of the given scope, capturing the backtrace
+    /// of where it was created, cloned or upgraded (the origin).
+    pub(crate) fn insert(&self, scope: Cow<'static, str>, origin: &'static str) -> usize {
+        let backtrace = Backtrace::force_capture();
+        self.guards.lock().unwrap().insert(LiveGuard {
+            scope,
+            origin,
+            backtrace,
+        })
+    }
+
+    pub(crate) fn remove(&self, key: usize) {
+        self.guards.lock().unwrap().try_remove(key);
+    }
+
+    /// Logs the backtraces of all live guards of the given scope,
+    /// returning the amount of live guards of that scope.
+    pub(crate) fn report(&self, scope: &str) -> usize {
+        let guards = self.guards.lock().unwrap();
+        let live_guards: Vec<_> = guards
+            .iter()
+            .map(|(_, guard)| guard)
+            .filter(|guard| guard.scope == scope)
+            .collect();
+        if live_guards.is_empty() {
+            return 0;
+        }
+        tracing::warn!(
+            "::shutdown: leak detection: {} guard(s) of '{}' still alive after {}s",
+            live_guards.len(),
+            scope,
+            self.threshold.as_secs_f64()
+        );
+        for guard in &live_guards {
+            tracing::warn!(
+                "::shutdown: leak detection: guard {} at:\n{}",
+                guard.origin,
+                guard.backtrace
+            );
+        }
+        live_guards.len()
+    }
+
+    /// Spawns a task which reports the live guards of the given scope once the
+    /// threshold has elapsed, unless the returned [`LeakCheck`] is dropped before that.
+    pub(crate) fn spawn_check(&self, scope: Cow<'static, str>) -> LeakCheck {
+        let detector = self.clone();
+        let handle = crate::sync::spawn(async move {
+            tokio::time::sleep(detector.threshold).await;
+            detector.report(&scope);
+        });
+        LeakCheck(handle.abort_handle())
+    }
+}
+
+/// Aborts the leak check spawned using [`LeakDetector::spawn_check`] once dropped.
+#[derive(Debug)]
+pub(crate) struct LeakCheck(AbortHandle);
+
+impl Drop for LeakCheck {
+    fn drop(&mut self) {
+        self.0.abort();
+    }
+}
@@ -3,7 +3,7 @@
     clippy::all,
     clippy::dbg_macro,
     clippy::todo,
-    clippy::empty_enum,
+    clippy::empty_enums,
     clippy::enum_glob_use,
     clippy::mem_forget,
     clippy::unused_self,
@@ -13,7 +13,6 @@
     clippy::match_wildcard_for_single_variants,
     clippy::if_let_mutex,
     clippy::await_holding_lock,
-    clippy::match_on_vec_items,
     clippy::imprecise_flops,
     clippy::suboptimal_flops,
     clippy::lossy_float_literal,
@@ -38,10 +37,73 @@
 mod guard;
 pub use guard::{ShutdownGuard, WeakShutdownGuard};
 
+mod future;
+pub use future::{Cancelled, CancelledOwned, FutureExt, UntilCancelled};
+
 mod shutdown;
-#[cfg(not(loom))]
-pub use shutdown::default_signal;
-pub use shutdown::{Shutdown, ShutdownBuilder};
+pub use shutdown::{Shutdown, ShutdownBuilder, ShutdownTrigger, TimeoutError};
+
+mod signal;
+pub use signal::OsSignal;
+#[cfg(all(not(loom), any(unix, windows)))]
+pub use signal::{default_signal, Escalation};
+
+mod report;
+pub use report::{DrainOutcome, PhaseReport, ShutdownReason, ShutdownReport};
+
+mod registry;
+pub use registry::GuardInfo;
+
+mod lifecycle;
+pub use lifecycle::Lifecycle;
+
+mod timeline;
+pub use timeline::{Timeline, TimelineEvent, TimelineEventKind};
+
+mod readiness;
+#[cfg(feature = "health")]
+pub use readiness::serve_health;
+pub use readiness::{Readiness, ReadinessState};
+
+#[cfg(all(feature = "systemd", unix))]
+mod systemd;
+#[cfg(all(feature = "systemd", unix))]
+pub use systemd::{systemd_notify, systemd_notify_ready};
+
+#[cfg(feature = "stream")]
+mod stream;
+#[cfg(feature = "stream")]
+pub use stream::{StreamExt, TakeUntilCancelled};
+
+#[cfg(feature = "net")]
+mod net;
+#[cfg(feature = "net")]
+pub use net::{Connection, ConnectionCounter, GracefulListener, Listener};
+
+#[cfg(feature = "hyper")]
+mod serve;
+#[cfg(feature = "hyper")]
+pub use serve::{serve_connection, serve_connection_with, ConnectionCloseFuture};
+
+#[cfg(feature = "tower")]
+mod layer;
+#[cfg(feature = "tower")]
+pub use layer::{
+    GracefulFuture, GracefulLayer, GracefulService, InFlightRequests, RejectStage, Rejection,
+    ServiceUnavailable,
+};
+
+#[cfg(feature = "metrics")]
+mod metrics;
+
+#[cfg(feature = "leak-detection")]
+mod leak;
+
+mod reload;
+pub use reload::{ReloadGuard, ReloadTrigger};
+
+mod task;
+pub use task::CriticalTaskExit;
 
 pub(crate) mod sync;
 pub(crate) mod trigger;
@@ -267,6 +329,1874 @@ mod tests {
         shutdown.shutdown().await;
     }
 
+    #[tokio::test]
+    async fn test_shutdown_child_cancelled_by_parent() {
+        let (tx, rx) = oneshot::channel::<()>();
+        let shutdown = Shutdown::new(async {
+            rx.await.unwrap();
+        });
+        let child = shutdown.guard().child();
+        let child_guard = child.guard_weak();
+        tx.send(()).unwrap();
+        child_guard.cancelled().await;
+        child.shutdown().await;
+        shutdown.shutdown().await;
+    }
+
+    #[tokio::test]
+    async fn test_shutdown_child_without_parent() {
+        let (tx, rx) = oneshot::channel::<()>();
+        let shutdown = Shutdown::new(std::future::pending::<()>());
+        let child = shutdown.guard().child_with_signal(async {
+            rx.await.unwrap();
+        });
+        child.spawn_task(async {
+            tokio::time::sleep(Duration::from_millis(10)).await;
+        });
+        tx.send(()).unwrap();
+        child.shutdown().await;
+
+        let weak_guard = shutdown.guard_weak();
+        let result = tokio::time::timeout(Duration::from_millis(50), weak_guard.cancelled()).await;
+        assert!(result.is_err(), "{result:?}");
+    }
+
+    #[tokio::test]
+    async fn test_shutdown_parent_waits_for_child_guards() {
+        let (tx, rx) = oneshot::channel::<()>();
+        let (child_tx, child_rx) = oneshot::channel::<()>();
+        let shutdown = Shutdown::new(async {
+            rx.await.unwrap();
+        });
+        let child = shutdown.guard().child();
+        let child_guard = child.guard();
+        drop(child);
+        tx.send(()).unwrap();
+
+        tokio::spawn(async move {
+            child_guard.cancelled().await;
+            child_rx.await.unwrap();
+            drop(child_guard);
+        });
+
+        let weak_guard = shutdown.guard_weak();
+        weak_guard.cancelled().await;
+        let shutdown = tokio::spawn(shutdown.shutdown());
+        tokio::time::sleep(Duration::from_millis(50)).await;
+        assert!(!shutdown.is_finished());
+
+        child_tx.send(()).unwrap();
+        shutdown.await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_shutdown_child_inherits_options() {
+        let shutdown = Shutdown::builder()
+            .with_signal(std::future::pending::<()>())
+            .with_abort_on_timeout()
+            .with_timeline()
+            .build();
+        let child = shutdown.guard().child();
+        child.spawn_task(std::future::pending::<()>());
+        child.shutdown_trigger().trigger();
+        let report = child
+            .shutdown_with_limit(Duration::from_millis(10))
+            .await
+            .unwrap_err()
+            .into_report();
+        assert_eq!(report.aborted_tasks(), 1);
+        assert!(report.timeline().is_some());
+    }
+
+    #[tokio::test]
+    async fn test_shutdown_with_parent() {
+        let (tx, rx) = oneshot::channel::<()>();
+        let shutdown = Shutdown::new(async {
+            rx.await.unwrap();
+        });
+        let child = Shutdown::builder()
+            .with_signal(std::future::pending::<()>())
+            .with_delay(Duration::from_millis(10))
+            .with_phase("flush", Duration::from_secs(1))
+            .with_parent(&shutdown.guard())
+            .build();
+        assert!(child.phase_guard("flush").is_some());
+        tx.send(()).unwrap();
+
+        let report = child.shutdown().await;
+        assert_eq!(report.reason(), Some(&ShutdownReason::ParentCancelled));
+        assert!(report.delay() >= Duration::from_millis(10));
+        assert_eq!(report.phases().len(), 1);
+        shutdown.shutdown().await;
+    }
+
+    #[tokio::test]
+    async fn test_shutdown_manual_trigger() {
+        let shutdown = Shutdown::new(std::future::pending::<()>());
+        let weak_guard = shutdown.guard_weak();
+        weak_guard.shutdown_trigger().trigger();
+        weak_guard.cancelled().await;
+        shutdown.shutdown().await;
+    }
+
+    #[tokio::test]
+    async fn test_shutdown_manual_trigger_with_delay() {
+        let shutdown = Shutdown::builder()
+            .with_delay(Duration::from_secs(5))
+            .with_signal(std::future::pending::<()>())
+            .build();
+        let guard = shutdown.guard();
+        shutdown.shutdown_trigger().trigger();
+
+        guard.shutdown_signal_triggered().await;
+        let result = tokio::time::timeout(Duration::from_micros(100), guard.cancelled()).await;
+        assert!(result.is_err(), "{result:?}");
+    }
+
+    #[tokio::test]
+    async fn test_shutdown_manual_trigger_child() {
+        let shutdown = Shutdown::new(std::future::pending::<()>());
+        let child = shutdown.guard().child();
+        child.shutdown_trigger().trigger();
+        child.shutdown().await;
+
+        let weak_guard = shutdown.guard_weak();
+        let result = tokio::time::timeout(Duration::from_millis(50), weak_guard.cancelled()).await;
+        assert!(result.is_err(), "{result:?}");
+    }
+
+    #[tokio::test]
+    async fn test_shutdown_reason_signal() {
+        let (tx, rx) = oneshot::channel::<()>();
+        let shutdown = Shutdown::new(async {
+            rx.await.unwrap();
+        });
+        let weak_guard = shutdown.guard_weak();
+        assert_eq!(weak_guard.shutdown_reason(), None);
+        tx.send(()).unwrap();
+        weak_guard.cancelled().await;
+        assert_eq!(weak_guard.shutdown_reason(), Some(ShutdownReason::Signal));
+        shutdown.shutdown().await;
+    }
+
+    #[tokio::test]
+    async fn test_shutdown_critical_task_returned() {
+        let shutdown = Shutdown::new(std::future::pending::<()>());
+        let weak_guard = shutdown.guard_weak();
+        let handle = shutdown.spawn_critical_task(async { 42 });
+        assert_eq!(handle.await.unwrap(), 42);
+        weak_guard.cancelled().await;
+        assert_eq!(
+            weak_guard.shutdown_reason(),
+            Some(ShutdownReason::CriticalTask(CriticalTaskExit::Returned))
+        );
+        shutdown.shutdown().await;
+    }
+
+    #[tokio::test]
+    async fn test_shutdown_critical_task_panicked() {
+        let shutdown = Shutdown::new(std::future::pending::<()>());
+        let handle = shutdown.spawn_critical_task_fn(|guard| async move {
+            tokio::time::sleep(Duration::from_millis(10)).await;
+            drop(guard);
+            panic!("boom");
+        });
+        assert!(handle.await.unwrap_err().is_panic());
+        assert_eq!(
+            shutdown.shutdown_reason(),
+            Some(ShutdownReason::CriticalTask(CriticalTaskExit::Panicked(
+                Some("boom".to_owned())
+            )))
+        );
+        shutdown.shutdown().await;
+    }
+
+    #[tokio::test]
+    async fn test_shutdown_critical_task_errored() {
+        let shutdown = Shutdown::new(std::future::pending::<()>());
+        let handle = shutdown.spawn_fallible_critical_task_fn(|guard| async move {
+            drop(guard);
+            Err::<(), _>("connection refused")
+        });
+        assert_eq!(handle.await.unwrap(), Err("connection refused"));
+        let report = shutdown.shutdown().await;
+        assert_eq!(
+            report.reason(),
+            Some(&ShutdownReason::CriticalTask(CriticalTaskExit::Errored(
+                "connection refused".to_owned()
+            )))
+        );
+    }
+
+    #[tokio::test]
+    async fn test_shutdown_fallible_critical_task_returned() {
+        let shutdown = Shutdown::new(std::future::pending::<()>());
+        let handle = shutdown.spawn_fallible_critical_task(async { Ok::<_, String>(42) });
+        assert_eq!(handle.await.unwrap(), Ok(42));
+        let report = shutdown.shutdown().await;
+        assert_eq!(
+            report.reason(),
+            Some(&ShutdownReason::CriticalTask(CriticalTaskExit::Returned))
+        );
+    }
+
+    #[tokio::test]
+    async fn test_shutdown_critical_task_after_signal() {
+        let shutdown = Shutdown::new(std::future::pending::<()>());
+        shutdown.spawn_critical_task_fn(|guard| async move {
+            guard.cancelled().await;
+        });
+        shutdown.shutdown_trigger().trigger();
+        shutdown.guard_weak().cancelled().await;
+        assert_eq!(shutdown.shutdown_reason(), Some(ShutdownReason::Manual));
+        shutdown.shutdown().await;
+    }
+
+    #[tokio::test]
+    async fn test_shutdown_report() {
+        let shutdown = Shutdown::builder()
+            .with_delay(Duration::from_millis(20))
+            .with_signal(std::future::pending::<()>())
+            .build();
+        let guard = shutdown.guard();
+        tokio::spawn(async move {
+            guard.cancelled().await;
+            tokio::time::sleep(Duration::from_millis(10)).await;
+            drop(guard);
+        });
+        shutdown.shutdown_trigger().trigger();
+        let report = shutdown.shutdown().await;
+        assert_eq!(report.reason(), Some(&ShutdownReason::Manual));
+        assert_eq!(report.outcome(), DrainOutcome::Completed);
+        assert!(report.delay() >= Duration::from_millis(20));
+        assert!(report.drain() >= Duration::from_millis(10));
+        assert_eq!(report.overwrite(), None);
+    }
+
+    #[tokio::test]
+    async fn test_shutdown_report_timeout() {
+        let (tx, rx) = oneshot::channel::<()>();
+        let shutdown = Shutdown::new(async {
+            rx.await.unwrap();
+        });
+        let _guard = shutdown.guard();
+        tx.send(()).unwrap();
+        let err = shutdown
+            .shutdown_with_limit(Duration::from_millis(10))
+            .await
+            .unwrap_err();
+        let report = err.report();
+        assert_eq!(report.reason(), Some(&ShutdownReason::Signal));
+        assert_eq!(report.outcome(), DrainOutcome::TimedOut);
+        assert!(report.drain() >= Duration::from_millis(10));
+    }
+
+    #[tokio::test]
+    async fn test_shutdown_report_overwritten() {
+        let (tx, rx) = oneshot::channel::<()>();
+        let (overwrite_tx, overwrite_rx) = oneshot::channel::<()>();
+        let shutdown = Shutdown::builder()
+            .with_signal(rx)
+            .with_overwrite_fn(|| overwrite_rx)
+            .build();
+        let _guard = shutdown.guard();
+        tx.send(()).unwrap();
+        overwrite_tx.send(()).unwrap();
+        let report = shutdown.shutdown().await;
+        assert_eq!(report.outcome(), DrainOutcome::Overwritten);
+        assert!(report.overwrite().is_some());
+    }
+
+    #[cfg(all(not(loom), any(unix, windows)))]
+    #[tokio::test]
+    async fn test_shutdown_escalation_immediate() {
+        let shutdown = Shutdown::builder()
+            .with_delay(Duration::from_secs(60))
+            .with_escalation(Escalation::new(1).with_message("press Ctrl-C again to force"))
+            .build();
+        let _guard = shutdown.guard();
+        shutdown.shutdown_trigger().trigger();
+        let report = shutdown.shutdown().await;
+        assert_eq!(report.reason(), Some(&ShutdownReason::Manual));
+        assert_eq!(report.outcome(), DrainOutcome::Overwritten);
+    }
+
+    #[cfg(all(not(loom), unix))]
+    #[test]
+    #[should_panic(expected = "at least one unix signal is required")]
+    fn test_shutdown_unix_signals_empty() {
+        let _ = Shutdown::builder().with_unix_signals([]);
+    }
+
+    #[cfg(all(not(loom), unix))]
+    #[tokio::test]
+    async fn test_shutdown_unix_signals() {
+        use tokio::signal::unix::SignalKind;
+
+        // ensure the signal handler is installed prior to sending the signal,
+        // as the default action of SIGUSR1 is to terminate the process
+        let _signal = tokio::signal::unix::signal(SignalKind::user_defined1()).unwrap();
+
+        let shutdown = Shutdown::builder()
+            .with_unix_signals([SignalKind::user_defined1()])
+            .build();
+        let weak_guard = shutdown.guard_weak();
+        let pid = std::process::id().to_string();
+        // keep sending the signal, as the shutdown might not be listening yet
+        while weak_guard.shutdown_reason().is_none() {
+            std::process::Command::new("kill")
+                .args(["-USR1", &pid])
+                .status()
+                .unwrap();
+            tokio::time::sleep(Duration::from_millis(10)).await;
+        }
+        let report = shutdown.shutdown().await;
+        let signal = report.reason().and_then(ShutdownReason::os_signal).unwrap();
+        assert_eq!(signal, OsSignal::Unix(SignalKind::user_defined1()));
+        assert_eq!(signal.to_string(), "SIGUSR1");
+    }
+
+    #[cfg(all(not(loom), unix))]
+    #[tokio::test]
+    async fn test_shutdown_unix_signals_escalation() {
+        use tokio::signal::unix::SignalKind;
+
+        let _signal = tokio::signal::unix::signal(SignalKind::user_defined2()).unwrap();
+
+        let shutdown = Shutdown::builder()
+            .with_unix_signals([SignalKind::user_defined2()])
+            .with_escalation(Escalation::new(2))
+            .build();
+        let _guard = shutdown.guard();
+        let weak_guard = shutdown.guard_weak();
+        let pid = std::process::id().to_string();
+        let kill = || {
+            std::process::Command::new("kill")
+                .args(["-USR2", &pid])
+                .status()
+                .unwrap();
+        };
+        while weak_guard.shutdown_reason().is_none() {
+            kill();
+            tokio::time::sleep(Duration::from_millis(10)).await;
+        }
+        // a single signal suffices, as the escalation keeps
+        // polling the signals which triggered the shutdown
+        kill();
+        let report = tokio::time::timeout(Duration::from_secs(5), shutdown.shutdown())
+            .await
+            .unwrap();
+        assert_eq!(report.outcome(), DrainOutcome::Overwritten);
+    }
+
+    #[tokio::test]
+    async fn test_shutdown_reload() {
+        let (tx, rx) = oneshot::channel::<()>();
+        let shutdown = Shutdown::new(rx);
+        let reload_trigger = shutdown.reload_trigger();
+        let mut reload_guard = shutdown.reload_guard();
+        let mut child_reload_guard = shutdown.guard().child().reload_guard();
+
+        reload_trigger.trigger();
+        reload_trigger.trigger();
+        assert!(reload_guard.reload_requested().await);
+        assert!(child_reload_guard.reload_requested().await);
+
+        tx.send(()).unwrap();
+        assert!(!reload_guard.reload_requested().await);
+        assert!(!child_reload_guard.reload_requested().await);
+        shutdown.shutdown().await;
+    }
+
+    #[cfg(all(not(loom), unix))]
+    #[tokio::test]
+    async fn test_shutdown_reload_signal() {
+        use tokio::signal::unix::SignalKind;
+
+        // ensure the signal handler is installed prior to sending the signal,
+        // as the default action of SIGUSR2 is to terminate the process
+        let _signal = tokio::signal::unix::signal(SignalKind::user_defined2()).unwrap();
+
+        let (tx, rx) = oneshot::channel::<()>();
+        let shutdown = Shutdown::builder()
+            .with_signal(rx)
+            .with_reload_signal(SignalKind::user_defined2())
+            .build();
+        let mut reload_guard = shutdown.reload_guard();
+        std::process::Command::new("kill")
+            .args(["-USR2", &std::process::id().to_string()])
+            .status()
+            .unwrap();
+        assert!(reload_guard.reload_requested().await);
+
+        tx.send(()).unwrap();
+        assert!(!reload_guard.reload_requested().await);
+        shutdown.shutdown().await;
+    }
+
+    #[tokio::test]
+    async fn test_shutdown_is_cancelled() {
+        let (tx, rx) = oneshot::channel::<()>();
+        let shutdown = Shutdown::builder()
+            .with_delay(Duration::from_millis(50))
+            .with_signal(rx)
+            .build();
+        let guard = shutdown.guard();
+        let weak_guard = shutdown.guard_weak();
+        assert!(!guard.is_shutdown_signal_triggered());
+        assert!(!weak_guard.is_cancelled());
+
+        tx.send(()).unwrap();
+        weak_guard.shutdown_signal_triggered().await;
+        assert!(guard.is_shutdown_signal_triggered());
+        assert!(!guard.is_cancelled());
+
+        weak_guard.cancelled().await;
+        assert!(guard.is_cancelled());
+        assert!(weak_guard.is_cancelled());
+        drop(guard);
+        shutdown.shutdown().await;
+    }
+
+    #[tokio::test]
+    async fn test_shutdown_is_cancelled_without_signal() {
+        let shutdown = Shutdown::no_signal();
+        let guard = shutdown.guard();
+        assert!(guard.is_cancelled());
+        assert!(guard.is_shutdown_signal_triggered());
+    }
+
+    #[tokio::test]
+    async fn test_shutdown_cancelled_owned() {
+        fn assert_unpin<T: Unpin + Send + 'static>(_: &T) {}
+
+        struct Worker {
+            cancelled: CancelledOwned,
+        }
+
+        let (tx, rx) = oneshot::channel::<()>();
+        let shutdown = Shutdown::new(rx);
+        let mut worker = Worker {
+            cancelled: shutdown.guard_weak().into_cancelled(),
+        };
+        assert_unpin(&worker.cancelled);
+
+        tokio::time::timeout(Duration::from_millis(10), &mut worker.cancelled)
+            .await
+            .unwrap_err();
+
+        tx.send(()).unwrap();
+        worker.cancelled.await;
+        shutdown.guard().cancelled().await;
+        shutdown.shutdown().await;
+    }
+
+    #[tokio::test]
+    async fn test_shutdown_run_until_cancelled() {
+        let (tx, rx) = oneshot::channel::<()>();
+        let shutdown = Shutdown::builder()
+            .with_delay(Duration::from_secs(60))
+            .with_signal(rx)
+            .build();
+        let guard = shutdown.guard();
+        assert_eq!(guard.run_until_cancelled(async { 42 }).await, Some(42));
+        assert_eq!(async { 42 }.until_cancelled(&guard).await, Some(42));
+
+        tx.send(()).unwrap();
+        let output = guard
+            .run_until_shutdown_signal_triggered(std::future::pending::<()>())
+            .await;
+        assert_eq!(output, None);
+        // the delay is still being awaited
+        tokio::time::timeout(
+            Duration::from_millis(10),
+            std::future::pending::<()>().until_cancelled(&shutdown.guard_weak()),
+        )
+        .await
+        .unwrap_err();
+    }
+
+    #[cfg(feature = "stream")]
+    #[tokio::test]
+    async fn test_shutdown_take_until_cancelled() {
+        use tokio_stream::StreamExt as _;
+
+        let (tx, rx) = oneshot::channel::<()>();
+        let (items_tx, items_rx) = tokio::sync::mpsc::unbounded_channel();
+        let shutdown = Shutdown::builder()
+            .with_delay(Duration::from_millis(50))
+            .with_signal(rx)
+            .build();
+        let guard = shutdown.guard();
+        let mut stream = tokio_stream::wrappers::UnboundedReceiverStream::new(items_rx)
+            .take_until_cancelled(&guard);
+
+        items_tx.send(1).unwrap();
+        assert_eq!(stream.next().await, Some(1));
+
+        tx.send(()).unwrap();
+        guard.shutdown_signal_triggered().await;
+        // items are still yielded during the delay
+        items_tx.send(2).unwrap();
+        assert_eq!(stream.next().await, Some(2));
+
+        guard.cancelled().await;
+        items_tx.send(3).unwrap();
+        assert_eq!(stream.next().await, None);
+        assert_eq!(stream.next().await, None);
+
+        drop(stream);
+        drop(guard);
+        shutdown.shutdown().await;
+    }
+
+    #[cfg(feature = "stream")]
+    #[tokio::test]
+    async fn test_shutdown_take_until_cancelled_drain_ready() {
+        use tokio_stream::StreamExt as _;
+
+        let (tx, rx) = oneshot::channel::<()>();
+        let (items_tx, items_rx) = tokio::sync::mpsc::unbounded_channel();
+        let shutdown = Shutdown::builder()
+            .with_delay(Duration::from_secs(60))
+            .with_signal(rx)
+            .build();
+        let guard = shutdown.guard();
+        let mut stream = tokio_stream::wrappers::UnboundedReceiverStream::new(items_rx)
+            .take_until_cancelled(&guard)
+            .drain_ready();
+
+        items_tx.send(1).unwrap();
+        assert_eq!(stream.next().await, Some(1));
+
+        items_tx.send(2).unwrap();
+        items_tx.send(3).unwrap();
+        tx.send(()).unwrap();
+        guard.shutdown_signal_triggered().await;
+        // buffered items are still yielded, but no new items are awaited
+        assert_eq!(stream.next().await, Some(2));
+        assert_eq!(stream.next().await, Some(3));
+        assert_eq!(stream.next().await, None);
+
+        items_tx.send(4).unwrap();
+        assert_eq!(stream.next().await, None);
+    }
+
+    #[cfg(feature = "net")]
+    #[tokio::test]
+    async fn test_shutdown_graceful_listener() {
+        let (tx, rx) = oneshot::channel::<()>();
+        let shutdown = Shutdown::builder()
+            .with_delay(Duration::from_millis(50))
+            .with_signal(rx)
+            .build();
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        let mut listener = GracefulListener::new(listener, &shutdown.guard_weak());
+        let connections = listener.connections();
+
+        let client = tokio::net::TcpStream::connect(addr).await.unwrap();
+        let (connection, _) = listener.accept().await.unwrap().unwrap();
+        assert_eq!(connections.open(), 1);
+        assert_eq!(connections.accepted(), 1);
+        assert!(!connection.guard().is_cancelled());
+
+        tx.send(()).unwrap();
+        let _pending_client = tokio::net::TcpStream::connect(addr).await.unwrap();
+        assert!(listener.accept().await.is_none());
+        drop(listener);
+
+        // the open connection keeps the shutdown from completing
+        let shutdown = tokio::spawn(shutdown.shutdown());
+        tokio::time::sleep(Duration::from_millis(100)).await;
+        assert!(!shutdown.is_finished());
+
+        drop(connection);
+        drop(client);
+        shutdown.await.unwrap();
+        assert_eq!(connections.open(), 0);
+        assert_eq!(connections.accepted(), 1);
+    }
+
+    #[cfg(feature = "hyper")]
+    #[tokio::test]
+    async fn test_shutdown_serve_connection() {
+        use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+        async fn request(client: &mut tokio::net::TcpStream) -> String {
+            client
+                .write_all(b"GET / HTTP/1.1\r\nhost: localhost\r\n\r\n")
+                .await
+                .unwrap();
+            let mut response = Vec::new();
+            while !response.ends_with(b"\r\n\r\nok") {
+                let mut buf = [0; 1024];
+                let n = client.read(&mut buf).await.unwrap();
+                assert!(n > 0, "connection closed before the response was read");
+                response.extend_from_slice(&buf[..n]);
+            }
+            String::from_utf8(response).unwrap().to_lowercase()
+        }
+
+        let (tx, rx) = oneshot::channel::<()>();
+        let shutdown = Shutdown::builder()
+            .with_delay(Duration::from_secs(60))
+            .with_signal(rx)
+            .build();
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        let server = shutdown.spawn_task_fn(move |guard| async move {
+            let (stream, _) = listener.accept().await.unwrap();
+            let service = hyper::service::service_fn(|_| async {
+                Ok::<_, std::convert::Infallible>(hyper::Response::new("ok".to_owned()))
+            });
+            serve_connection(hyper_util::rt::TokioIo::new(stream), service, guard).await
+        });
+
+        let mut client = tokio::net::TcpStream::connect(addr).await.unwrap();
+        assert!(!request(&mut client).await.contains("connection: close"));
+
+        tx.send(()).unwrap();
+        shutdown.guard().shutdown_signal_triggered().await;
+        assert!(request(&mut client).await.contains("connection: close"));
+
+        // the connection is closed by the server after the response
+        assert_eq!(client.read(&mut [0; 1]).await.unwrap(), 0);
+        server.await.unwrap().unwrap();
+    }
+
+    #[cfg(feature = "tower")]
+    #[tokio::test]
+    async fn test_shutdown_graceful_layer() {
+        use std::{future::Future, pin::Pin};
+        use tower_layer::Layer;
+        use tower_service::Service;
+
+        struct Hello;
+
+        impl Service<http::Request<()>> for Hello {
+            type Response = http::Response<String>;
+            type Error = std::convert::Infallible;
+            type Future = Pin<Box<dyn Future<Output = Result<Self::Response, Self::Error>>>>;
+
+            fn poll_ready(
+                &mut self,
+                _: &mut std::task::Context<'_>,
+            ) -> std::task::Poll<Result<(), Self::Error>> {
+                std::task::Poll::Ready(Ok(()))
+            }
+
+            fn call(&mut self, _: http::Request<()>) -> Self::Future {
+                Box::pin(async {
+                    tokio::time::sleep(Duration::from_millis(100)).await;
+                    Ok(http::Response::new("ok".to_owned()))
+                })
+            }
+        }
+
+        let (tx, rx) = oneshot::channel::<()>();
+        let shutdown = Shutdown::builder()
+            .with_delay(Duration::from_millis(10))
+            .with_signal(rx)
+            .build();
+        let layer = GracefulLayer::new(&shutdown.guard_weak())
+            .reject_at(RejectStage::ShutdownSignal)
+            .with_rejection(ServiceUnavailable::new().with_retry_after(Duration::from_secs(5)));
+        let in_flight_requests = layer.in_flight_requests();
+        let mut service = layer.layer(Hello);
+
+        let in_flight = service.call(http::Request::new(()));
+        assert_eq!(in_flight_requests.count(), 1);
+
+        tx.send(()).unwrap();
+        shutdown.guard().shutdown_signal_triggered().await;
+        let rejected = service.call(http::Request::new(())).await.unwrap();
+        assert_eq!(rejected.status(), http::StatusCode::SERVICE_UNAVAILABLE);
+        assert_eq!(rejected.headers()[http::header::RETRY_AFTER], "5");
+        assert_eq!(in_flight_requests.count(), 1);
+
+        // the in-flight request keeps the shutdown from completing
+        let shutdown = tokio::spawn(shutdown.shutdown());
+        tokio::time::sleep(Duration::from_millis(50)).await;
+        assert!(!shutdown.is_finished());
+
+        assert_eq!(in_flight.await.unwrap().into_body(), "ok");
+        assert_eq!(in_flight_requests.count(), 0);
+        shutdown.await.unwrap();
+    }
+
+    #[cfg(feature = "tower")]
+    #[tokio::test]
+    async fn test_shutdown_graceful_layer_poll_ready() {
+        use std::{
+            future::{poll_fn, Ready},
+            sync::atomic::{AtomicUsize, Ordering},
+            sync::Arc,
+        };
+        use tower_layer::Layer;
+        use tower_service::Service;
+
+        /// A service which reserves a permit once ready, released once called.
+        #[derive(Clone, Default)]
+        struct Limited {
+            permits: Arc<AtomicUsize>,
+            reserved: bool,
+        }
+
+        impl Service<http::Request<()>> for Limited {
+            type Response = http::Response<String>;
+            type Error = std::convert::Infallible;
+            type Future = Ready<Result<Self::Response, Self::Error>>;
+
+            fn poll_ready(
+                &mut self,
+                _: &mut std::task::Context<'_>,
+            ) -> std::task::Poll<Result<(), Self::Error>> {
+                if !self.reserved {
+                    self.reserved = true;
+                    self.permits.fetch_add(1, Ordering::SeqCst);
+                }
+                std::task::Poll::Ready(Ok(()))
+            }
+
+            fn call(&mut self, _: http::Request<()>) -> Self::Future {
+                assert!(self.reserved, "called without being ready");
+                self.reserved = false;
+                self.permits.fetch_sub(1, Ordering::SeqCst);
+                std::future::ready(Ok(http::Response::new("ok".to_owned())))
+            }
+        }
+
+        let (tx, rx) = oneshot::channel::<()>();
+        let shutdown = Shutdown::new(rx);
+        let inner = Limited::default();
+        let permits = inner.permits.clone();
+        let mut service = GracefulLayer::new(&shutdown.guard_weak()).layer(inner);
+
+        // a service which is ready before the shutdown still passes the request on
+        poll_fn(|cx| service.poll_ready(cx)).await.unwrap();
+        tx.send(()).unwrap();
+        shutdown.guard().cancelled().await;
+        let response = service.call(http::Request::new(())).await.unwrap();
+        assert_eq!(response.status(), http::StatusCode::OK);
+        assert_eq!(permits.load(Ordering::SeqCst), 0);
+
+        // once rejecting, the wrapped service does not reserve a permit
+        poll_fn(|cx| service.poll_ready(cx)).await.unwrap();
+        let response = service.call(http::Request::new(())).await.unwrap();
+        assert_eq!(response.status(), http::StatusCode::SERVICE_UNAVAILABLE);
+        assert_eq!(permits.load(Ordering::SeqCst), 0);
+
+        shutdown.shutdown().await;
+    }
+
+    #[tokio::test]
+    async fn test_shutdown_lifecycle() {
+        let (tx, rx) = oneshot::channel::<()>();
+        let shutdown = Shutdown::builder()
+            .with_delay(Duration::from_millis(100))
+            .with_signal(rx)
+            .build();
+        let mut watch = shutdown.watch_lifecycle();
+        assert_eq!(shutdown.lifecycle(), Lifecycle::Running);
+
+        let guard_a = shutdown.guard();
+        let guard_b = shutdown.guard();
+        tx.send(()).unwrap();
+        watch.changed().await.unwrap();
+        assert_eq!(
+            *watch.borrow_and_update(),
+            Lifecycle::SignalReceived(Duration::from_millis(100))
+        );
+        watch.changed().await.unwrap();
+        assert_eq!(*watch.borrow_and_update(), Lifecycle::Cancelled);
+
+        let shutdown = tokio::spawn(shutdown.shutdown());
+        watch
+            .wait_for(|state| matches!(state, Lifecycle::Draining { .. }))
+            .await
+            .unwrap();
+        assert_eq!(
+            guard_a.lifecycle(),
+            Lifecycle::Draining {
+                remaining_guards: 2
+            }
+        );
+        drop(guard_a);
+        assert_eq!(
+            guard_b.lifecycle(),
+            Lifecycle::Draining {
+                remaining_guards: 1
+            }
+        );
+        drop(guard_b);
+        shutdown.await.unwrap();
+        assert_eq!(*watch.borrow(), Lifecycle::Completed);
+    }
+
+    #[tokio::test]
+    async fn test_shutdown_lifecycle_timeout() {
+        let shutdown = Shutdown::no_signal();
+        let guard = shutdown.guard();
+        let mut watch = guard.watch_lifecycle();
+        shutdown
+            .shutdown_with_limit(Duration::from_millis(10))
+            .await
+            .unwrap_err();
+        assert_eq!(*watch.borrow_and_update(), Lifecycle::TimedOut);
+        // dropping a guard after the drain ended does not change the lifecycle
+        drop(guard);
+        assert!(!watch.has_changed().unwrap());
+    }
+
+    #[tokio::test]
+    async fn test_shutdown_lifecycle_phases() {
+        let shutdown = Shutdown::builder()
+            .without_signal()
+            .with_phase("flush", Duration::from_secs(60))
+            .build();
+        let readiness = shutdown.readiness();
+        let mut watch = shutdown.watch_lifecycle();
+        let flush_guard = shutdown.phase_guard("flush").unwrap();
+
+        let shutdown = tokio::spawn(shutdown.shutdown());
+        watch
+            .wait_for(|state| *state == Lifecycle::RunningPhases)
+            .await
+            .unwrap();
+        // the shutdown is not done while its phases are still running
+        assert!(!watch.borrow().is_done());
+        assert_eq!(readiness.state(), ReadinessState::Draining);
+
+        drop(flush_guard);
+        let report = shutdown.await.unwrap();
+        assert!(report.is_graceful());
+        assert_eq!(*watch.borrow(), Lifecycle::Completed);
+        assert_eq!(readiness.state(), ReadinessState::Stopped);
+    }
+
+    #[tokio::test]
+    async fn test_shutdown_readiness() {
+        let (tx, rx) = oneshot::channel::<()>();
+        let shutdown = Shutdown::builder()
+            .with_delay(Duration::from_millis(50))
+            .with_signal(rx)
+            .build();
+        let readiness = shutdown.readiness();
+        let mut watch = readiness.watch();
+        assert_eq!(readiness.state(), ReadinessState::Starting);
+
+        readiness.set_ready();
+        assert!(readiness.is_ready());
+        assert!(watch.has_changed().unwrap());
+
+        let guard = shutdown.guard();
+        tx.send(()).unwrap();
+        watch
+            .wait_for(|state| *state == ReadinessState::Draining)
+            .await
+            .unwrap();
+        // readiness only moves forward
+        readiness.set_ready();
+        assert_eq!(guard.readiness().state(), ReadinessState::Draining);
+
+        shutdown
+            .shutdown_with_limit(Duration::from_millis(10))
+            .await
+            .unwrap_err();
+        assert_eq!(readiness.state(), ReadinessState::Stopped);
+        drop(guard);
+    }
+
+    #[tokio::test]
+    async fn test_shutdown_readiness_stopped() {
+        let shutdown = Shutdown::no_signal();
+        let readiness = shutdown.readiness();
+        readiness.set_ready();
+        let guard = shutdown.guard_weak();
+        shutdown.shutdown().await;
+        assert_eq!(guard.readiness().state(), ReadinessState::Stopped);
+    }
+
+    #[cfg(feature = "health")]
+    #[tokio::test]
+    async fn test_shutdown_serve_health() {
+        use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+        async fn get(addr: std::net::SocketAddr, path: &str) -> String {
+            let mut stream = tokio::net::TcpStream::connect(addr).await.unwrap();
+            stream
+                .write_all(format!("GET {} HTTP/1.1\r\nhost: localhost\r\n\r\n", path).as_bytes())
+                .await
+                .unwrap();
+            let mut response = String::new();
+            stream.read_to_string(&mut response).await.unwrap();
+            response
+        }
+
+        let (tx, rx) = oneshot::channel::<()>();
+        let shutdown = Shutdown::builder().with_signal(rx).build();
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        let server = tokio::spawn(serve_health(listener, shutdown.readiness()));
+
+        let response = get(addr, "/readyz").await;
+        assert!(response.starts_with("HTTP/1.1 503"));
+        assert!(response.ends_with("starting"));
+        assert!(get(addr, "/livez").await.starts_with("HTTP/1.1 200"));
+        assert!(get(addr, "/foo").await.starts_with("HTTP/1.1 404"));
+
+        shutdown.readiness().set_ready();
+        assert!(get(addr, "/readyz").await.starts_with("HTTP/1.1 200"));
+
+        tx.send(()).unwrap();
+        shutdown.shutdown().await;
+        server.await.unwrap();
+    }
+
+    #[cfg(feature = "health")]
+    #[tokio::test(start_paused = true)]
+    async fn test_shutdown_serve_health_idle_connection() {
+        use tokio::io::AsyncReadExt;
+
+        let readiness = Readiness::default();
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        tokio::spawn(serve_health(listener, readiness));
+
+        // the connection never sends a request, and is closed once timed out
+        let mut stream = tokio::net::TcpStream::connect(addr).await.unwrap();
+        let mut response = Vec::new();
+        let n = tokio::time::timeout(Duration::from_secs(60), stream.read_to_end(&mut response))
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(n, 0);
+    }
+
+    #[cfg(all(feature = "systemd", unix))]
+    #[tokio::test]
+    async fn test_shutdown_systemd_notify() {
+        let path =
+            std::env::temp_dir().join(format!("tokio-graceful-notify-{}.sock", std::process::id()));
+        let _ = std::fs::remove_file(&path);
+        let socket = std::os::unix::net::UnixDatagram::bind(&path).unwrap();
+        socket
+            .set_read_timeout(Some(Duration::from_secs(5)))
+            .unwrap();
+        let recv = || {
+            let mut buf = [0; 128];
+            let n = socket.recv(&mut buf).unwrap();
+            String::from_utf8(buf[..n].to_vec()).unwrap()
+        };
+        // the only test which reads $NOTIFY_SOCKET
+        std::env::set_var("NOTIFY_SOCKET", &path);
+
+        assert!(systemd_notify_ready().unwrap());
+        assert_eq!(recv(), "READY=1");
+
+        let (tx, rx) = oneshot::channel::<()>();
+        let shutdown = Shutdown::builder()
+            .with_signal(rx)
+            .with_phase("flush", Duration::from_secs(2))
+            .with_phase("stuck", Duration::from_millis(10))
+            .with_abort_on_timeout()
+            .with_systemd_notify()
+            .build();
+        shutdown.readiness().set_ready();
+        assert_eq!(recv(), "READY=1");
+
+        shutdown
+            .phase_guard("stuck")
+            .unwrap()
+            .spawn_task(std::future::pending::<()>());
+        tx.send(()).unwrap();
+        let err = shutdown
+            .shutdown_with_limit(Duration::from_secs(10))
+            .await
+            .unwrap_err();
+        assert_eq!(err.report().phases()[1].aborted_tasks(), 1);
+        assert_eq!(recv(), "STOPPING=1");
+        // the timeout is extended for each stage of the shutdown
+        assert_eq!(recv(), "EXTEND_TIMEOUT_USEC=10000000");
+        assert_eq!(recv(), "EXTEND_TIMEOUT_USEC=2000000");
+        assert_eq!(recv(), "EXTEND_TIMEOUT_USEC=10000");
+        assert_eq!(recv(), "EXTEND_TIMEOUT_USEC=5000000");
+
+        std::env::remove_var("NOTIFY_SOCKET");
+        let _ = std::fs::remove_file(&path);
+    }
+
+    #[cfg(feature = "metrics")]
+    #[test]
+    fn test_shutdown_metrics() {
+        use metrics_util::debugging::{DebugValue, DebuggingRecorder};
+
+        let recorder = DebuggingRecorder::new();
+        let snapshotter = recorder.snapshotter();
+        let snapshot = || {
+            snapshotter
+                .snapshot()
+                .into_vec()
+                .into_iter()
+                .map(|(key, _, _, value)| {
+                    let (_, key) = key.into_parts();
+                    let labels: Vec<_> = key
+                        .labels()
+                        .map(|label| format!("{}={}", label.key(), label.value()))
+                        .collect();
+                    (format!("{}{:?}", key.name(), labels), value)
+                })
+                .collect::<std::collections::HashMap<_, _>>()
+        };
+
+        ::metrics::with_local_recorder(&recorder, || {
+            let runtime = tokio::runtime::Builder::new_current_thread()
+                .enable_all()
+                .build()
+                .unwrap();
+            runtime.block_on(async {
+                let (tx, rx) = oneshot::channel::<()>();
+                let shutdown = Shutdown::builder()
+                    .with_signal(rx)
+                    .with_delay(Duration::from_millis(50))
+                    .with_phase("flush", Duration::from_secs(60))
+                    .build();
+                for _ in 0..2 {
+                    shutdown.spawn_task_fn(|guard| async move {
+                        guard.cancelled().await;
+                    });
+                }
+                let flush_guard = shutdown.phase_guard("flush").unwrap();
+                let child = shutdown.guard().child();
+                child.spawn_task_fn(|guard| async move {
+                    guard.cancelled().await;
+                });
+                drop(child);
+                // the guards and tasks held by the shutdown, its phase
+                // and the child (on behalf of its parent) are not counted
+                let metrics = snapshot();
+                assert_eq!(
+                    metrics["tokio_graceful_guards[\"scope=main\"]"],
+                    DebugValue::Gauge(2.0.into())
+                );
+                assert_eq!(
+                    metrics["tokio_graceful_guards[\"scope=flush\"]"],
+                    DebugValue::Gauge(1.0.into())
+                );
+                assert_eq!(
+                    metrics["tokio_graceful_guards[\"scope=main/child-1\"]"],
+                    DebugValue::Gauge(1.0.into())
+                );
+                assert_eq!(
+                    metrics["tokio_graceful_tasks_spawned_total[]"],
+                    DebugValue::Counter(3)
+                );
+                drop(flush_guard);
+                tx.send(()).unwrap();
+                shutdown.shutdown().await;
+            });
+        });
+
+        let metrics = snapshot();
+        // taking a snapshot resets the values, hence the guards dropped since are negative
+        assert_eq!(
+            metrics["tokio_graceful_guards[\"scope=main\"]"],
+            DebugValue::Gauge((-2.0).into())
+        );
+        assert_eq!(
+            metrics["tokio_graceful_guards[\"scope=flush\"]"],
+            DebugValue::Gauge((-1.0).into())
+        );
+        assert_eq!(
+            metrics["tokio_graceful_guards[\"scope=main/child-1\"]"],
+            DebugValue::Gauge((-1.0).into())
+        );
+        assert_eq!(
+            metrics["tokio_graceful_shutdowns_total[\"outcome=graceful\"]"],
+            DebugValue::Counter(1)
+        );
+        let DebugValue::Histogram(delay) = &metrics["tokio_graceful_shutdown_delay_seconds[]"]
+        else {
+            panic!("delay is not a histogram");
+        };
+        assert_eq!(delay.len(), 1);
+        assert!(delay[0].into_inner() >= 0.05);
+        let DebugValue::Histogram(drain) = &metrics["tokio_graceful_shutdown_drain_seconds[]"]
+        else {
+            panic!("drain is not a histogram");
+        };
+        assert_eq!(drain.len(), 1);
+    }
+
+    #[test]
+    #[should_panic(expected = "a phase named \"flush\" was already added")]
+    fn test_shutdown_phase_duplicate_name() {
+        let _ = Shutdown::builder()
+            .with_phase("flush", Duration::from_secs(1))
+            .with_phase("flush", Duration::from_secs(1));
+    }
+
+    #[test]
+    #[should_panic(expected = "the phase name \"main\" is reserved")]
+    fn test_shutdown_phase_reserved_name() {
+        let _ = Shutdown::builder().with_phase("main", Duration::from_secs(1));
+    }
+
+    #[tokio::test]
+    async fn test_shutdown_phases_in_order() {
+        let (tx, rx) = oneshot::channel::<()>();
+        let shutdown = Shutdown::builder()
+            .with_signal(rx)
+            .with_phase("drain", Duration::from_secs(60))
+            .with_phase("flush", Duration::from_secs(60))
+            .build();
+        assert!(shutdown.phase_guard("unknown").is_none());
+
+        let guard = shutdown.guard();
+        let drain_guard = shutdown.phase_guard("drain").unwrap();
+        let flush_guard = shutdown.phase_guard("flush").unwrap();
+        let (order_tx, mut order_rx) = tokio::sync::mpsc::unbounded_channel();
+
+        let main_order_tx = order_tx.clone();
+        tokio::spawn(async move {
+            guard.cancelled().await;
+            main_order_tx.send("main").unwrap();
+        });
+        let drain_order_tx = order_tx.clone();
+        tokio::spawn(async move {
+            drain_guard.cancelled().await;
+            drain_order_tx.send("drain").unwrap();
+        });
+        tokio::spawn(async move {
+            flush_guard.cancelled().await;
+            order_tx.send("flush").unwrap();
+        });
+
+        tx.send(()).unwrap();
+        let report = shutdown.shutdown().await;
+
+        let mut order = Vec::new();
+        while let Some(name) = order_rx.recv().await {
+            order.push(name);
+        }
+        assert_eq!(order, ["main", "drain", "flush"]);
+
+        let phases: Vec<_> = report.phases().iter().map(|phase| phase.name()).collect();
+        assert_eq!(phases, ["drain", "flush"]);
+        assert!(report
+            .phases()
+            .iter()
+            .all(|phase| phase.outcome() == DrainOutcome::Completed));
+    }
+
+    #[tokio::test]
+    async fn test_shutdown_timeline() {
+        let (tx, rx) = oneshot::channel::<()>();
+        let shutdown = Shutdown::builder()
+            .with_signal(rx)
+            .with_delay(Duration::from_millis(10))
+            .with_phase("flush", Duration::from_secs(60))
+            .with_timeline()
+            .build();
+        shutdown.spawn_task_fn_named("worker", |guard| async move {
+            guard.cancelled().await;
+        });
+        let flush_guard = shutdown.phase_guard("flush").unwrap();
+        tokio::spawn(async move {
+            flush_guard.cancelled().await;
+        });
+
+        tx.send(()).unwrap();
+        let report = shutdown.shutdown().await;
+        let timeline = report.timeline().unwrap();
+
+        let kinds: Vec<_> = timeline
+            .events()
+            .iter()
+            .map(|event| event.kind())
+            .filter(|kind| !matches!(kind, TimelineEventKind::GuardReleased { .. }))
+            .cloned()
+            .collect();
+        assert_eq!(
+            kinds,
+            [
+                TimelineEventKind::SignalReceived(ShutdownReason::Signal),
+                TimelineEventKind::DelayStarted(Duration::from_millis(10)),
+                TimelineEventKind::DelayEnded,
+                TimelineEventKind::Cancelled,
+                TimelineEventKind::PhaseStarted {
+                    name: "flush".into()
+                },
+                TimelineEventKind::PhaseEnded {
+                    name: "flush".into(),
+                    outcome: DrainOutcome::Completed
+                },
+                TimelineEventKind::Finished(DrainOutcome::Completed),
+            ]
+        );
+        assert!(timeline.events().iter().any(|event| event.kind()
+            == &TimelineEventKind::GuardReleased {
+                scope: "main".into(),
+                name: Some("worker".into()),
+                remaining_guards: 0
+            }));
+        assert!(timeline.events().iter().any(|event| event.kind()
+            == &TimelineEventKind::GuardReleased {
+                scope: "flush".into(),
+                name: None,
+                remaining_guards: 0
+            }));
+        assert!(timeline
+            .events()
+            .windows(2)
+            .all(|events| events[0].elapsed() <= events[1].elapsed()));
+
+        let json = timeline.to_json();
+        assert!(json.contains(r#""event":"signal_received","reason":"shutdown signal triggered""#));
+        assert!(json.contains(
+            r#""event":"guard_released","scope":"main","name":"worker","remaining_guards":0"#
+        ));
+        assert!(json.contains(r#""event":"finished","outcome":"completed""#));
+
+        let path = std::env::temp_dir().join(format!(
+            "tokio-graceful-timeline-{}.json",
+            std::process::id()
+        ));
+        timeline.write_chrome_trace(&path).unwrap();
+        let trace = std::fs::read_to_string(&path).unwrap();
+        let _ = std::fs::remove_file(&path);
+        assert!(trace.starts_with(r#"{"traceEvents":["#));
+        assert_eq!(trace.matches(r#""ph":"B""#).count(), 3);
+        assert_eq!(trace.matches(r#""ph":"E""#).count(), 3);
+        assert!(trace.contains(r#""name":"phase: flush""#));
+    }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+    async fn test_shutdown_timeline_multi_thread() {
+        for _ in 0..20 {
+            let (tx, rx) = oneshot::channel::<()>();
+            let shutdown = Shutdown::builder()
+                .with_signal(rx)
+                .with_phase("flush", Duration::from_secs(60))
+                .with_timeline()
+                .build();
+            for _ in 0..8 {
+                shutdown.spawn_task_fn(|guard| async move {
+                    guard.cancelled().await;
+                });
+            }
+            tx.send(()).unwrap();
+            let report = shutdown.shutdown().await;
+            let events = report.timeline().unwrap().events();
+
+            // the last guard is released before the drain continues
+            let index = events
+                .iter()
+                .position(|event| {
+                    matches!(
+                        event.kind(),
+                        TimelineEventKind::PhaseStarted { .. } | TimelineEventKind::Finished(_)
+                    )
+                })
+                .unwrap();
+            assert_eq!(
+                events[index - 1].kind(),
+                &TimelineEventKind::GuardReleased {
+                    scope: "main".into(),
+                    name: None,
+                    remaining_guards: 0
+                },
+                "{:?}",
+                events
+            );
+        }
+    }
+
+    #[tokio::test]
+    async fn test_shutdown_timeline_phase_guard_released_during_drain() {
+        let (tx, rx) = oneshot::channel::<()>();
+        let shutdown = Shutdown::builder()
+            .with_signal(rx)
+            .with_phase("flush", Duration::from_secs(60))
+            .with_timeline()
+            .build();
+        let flush_guard = shutdown.phase_guard("flush").unwrap().named("flusher");
+        shutdown.spawn_task_fn(move |guard| async move {
+            guard.cancelled().await;
+            drop(flush_guard);
+        });
+        tx.send(()).unwrap();
+        let report = shutdown.shutdown().await;
+        let events = report.timeline().unwrap().events();
+
+        let released = events
+            .iter()
+            .position(|event| {
+                event.kind()
+                    == &TimelineEventKind::GuardReleased {
+                        scope: "flush".into(),
+                        name: Some("flusher".into()),
+                        remaining_guards: 1,
+                    }
+            })
+            .unwrap();
+        let phase_started = events
+            .iter()
+            .position(|event| matches!(event.kind(), TimelineEventKind::PhaseStarted { .. }))
+            .unwrap();
+        assert!(released < phase_started, "{:?}", events);
+    }
+
+    #[tokio::test]
+    async fn test_shutdown_timeline_phase_timeout() {
+        let (tx, rx) = oneshot::channel::<()>();
+        let shutdown = Shutdown::builder()
+            .with_signal(rx)
+            .with_phase("stuck", Duration::from_millis(10))
+            .with_timeline()
+            .build();
+        let _stuck_guard = shutdown.phase_guard("stuck").unwrap();
+        tx.send(()).unwrap();
+        let report = shutdown.shutdown().await;
+        assert_eq!(report.outcome(), DrainOutcome::Completed);
+        assert!(!report.is_graceful());
+
+        let timeline = report.timeline().unwrap();
+        assert_eq!(
+            timeline.events().last().unwrap().kind(),
+            &TimelineEventKind::Finished(DrainOutcome::TimedOut)
+        );
+        assert!(timeline
+            .to_json()
+            .contains(r#""event":"finished","outcome":"timed_out""#));
+    }
+
+    #[test]
+    fn test_timeline_recorded_in_order() {
+        let recorder = timeline::TimelineRecorder::default();
+        std::thread::scope(|scope| {
+            for _ in 0..4 {
+                scope.spawn(|| {
+                    for _ in 0..1000 {
+                        recorder.record(TimelineEventKind::DelayEnded);
+                    }
+                });
+            }
+        });
+        let timeline = recorder.timeline();
+        assert_eq!(timeline.events().len(), 4000);
+        assert!(timeline
+            .events()
+            .windows(2)
+            .all(|events| events[0].elapsed() <= events[1].elapsed()));
+    }
+
+    #[tokio::test]
+    async fn test_shutdown_phase_timeout() {
+        let (tx, rx) = oneshot::channel::<()>();
+        let shutdown = Shutdown::builder()
+            .with_signal(rx)
+            .with_phase("stuck", Duration::from_millis(10))
+            .with_phase("flush", Duration::from_secs(60))
+            .build();
+        let _stuck_guard = shutdown.phase_guard("stuck").unwrap();
+        tx.send(()).unwrap();
+        let err = shutdown
+            .shutdown_with_limit(Duration::from_secs(60))
+            .await
+            .unwrap_err();
+        assert!(err.to_string().starts_with("phase 'stuck' timeout after"));
+        let report = err.report();
+        assert_eq!(report.outcome(), DrainOutcome::Completed);
+        assert!(!report.is_graceful());
+        let phases = report.phases();
+        assert_eq!(phases.len(), 2);
+        assert_eq!(phases[0].outcome(), DrainOutcome::TimedOut);
+        assert_eq!(phases[1].outcome(), DrainOutcome::Completed);
+    }
+
+    #[tokio::test]
+    async fn test_shutdown_live_guards() {
+        let shutdown = Shutdown::no_signal();
+        let guard = shutdown.guard().named("consumer");
+        let guard_clone = guard.clone();
+        let _unnamed_guard = shutdown.guard();
+
+        let live_guards = shutdown.live_guards();
+        assert_eq!(live_guards.len(), 2);
+        assert!(live_guards.iter().all(|info| info.name() == "consumer"));
+        assert_eq!(live_guards[0].location().file(), file!());
+
+        drop(guard);
+        drop(guard_clone);
+        assert!(shutdown.live_guards().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_shutdown_live_guards_timeout() {
+        let (tx, rx) = oneshot::channel::<()>();
+        let shutdown = Shutdown::new(rx);
+        shutdown.spawn_task_named("stuck", std::future::pending::<()>());
+        shutdown.spawn_task_named("done", async {});
+        tx.send(()).unwrap();
+        let err = shutdown
+            .shutdown_with_limit(Duration::from_millis(10))
+            .await
+            .unwrap_err();
+        let names: Vec<_> = err.live_guards().iter().map(|info| info.name()).collect();
+        assert_eq!(names, ["stuck"]);
+    }
+
+    #[tokio::test]
+    async fn test_shutdown_live_guards_per_phase() {
+        let (tx, rx) = oneshot::channel::<()>();
+        let shutdown = Shutdown::builder()
+            .with_signal(rx)
+            .with_phase("flush", Duration::from_millis(10))
+            .build();
+        let _flusher = shutdown.phase_guard("flush").unwrap().named("flusher");
+        shutdown.spawn_task_named("stuck", std::future::pending::<()>());
+        let names: Vec<_> = shutdown
+            .live_guards()
+            .iter()
+            .map(|info| info.name().to_owned())
+            .collect();
+        assert_eq!(names, ["stuck"]);
+
+        tx.send(()).unwrap();
+        let err = shutdown
+            .shutdown_with_limit(Duration::from_millis(10))
+            .await
+            .unwrap_err();
+        let names: Vec<_> = err.live_guards().iter().map(|info| info.name()).collect();
+        assert_eq!(names, ["stuck"]);
+        let phase = &err.report().phases()[0];
+        assert_eq!(phase.outcome(), DrainOutcome::TimedOut);
+        let names: Vec<_> = phase.live_guards().iter().map(|info| info.name()).collect();
+        assert_eq!(names, ["flusher"]);
+    }
+
+    /// Captures the logs (of the current thread) for as long as the returned guard is alive.
+    #[derive(Clone, Default)]
+    struct Logs(std::sync::Arc<std::sync::Mutex<Vec<u8>>>);
+
+    impl Logs {
+        fn capture(level: tracing::Level) -> (Self, tracing::subscriber::DefaultGuard) {
+            let logs = Logs::default();
+            let subscriber = tracing_subscriber::fmt()
+                .with_max_level(level)
+                .with_ansi(false)
+                .with_writer({
+                    let logs = logs.clone();
+                    move || logs.clone()
+                })
+                .finish();
+            (logs, tracing::subscriber::set_default(subscriber))
+        }
+
+        fn contents(&self) -> String {
+            String::from_utf8(self.0.lock().unwrap().clone()).unwrap()
+        }
+    }
+
+    impl std::io::Write for Logs {
+        fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+            self.0.lock().unwrap().write(buf)
+        }
+
+        fn flush(&mut self) -> std::io::Result<()> {
+            Ok(())
+        }
+    }
+
+    #[tokio::test]
+    async fn test_shutdown_task_span() {
+        let (logs, _default) = Logs::capture(tracing::Level::DEBUG);
+
+        let (tx, rx) = oneshot::channel::<()>();
+        let shutdown = Shutdown::new(rx);
+        shutdown.spawn_task_fn_named("worker", |guard| async move {
+            guard.cancelled().await;
+        });
+        tx.send(()).unwrap();
+        shutdown.shutdown().await;
+
+        let logs = logs.contents();
+        let task_logs: Vec<_> = logs
+            .lines()
+            .filter(|line| line.contains("task{name=worker}"))
+            .collect();
+        assert_eq!(task_logs.len(), 2, "{}", logs);
+        assert!(task_logs[0].contains("first polled"));
+        assert!(task_logs[0].contains("after cancellation"));
+        assert!(task_logs[1].contains("released guard"));
+        assert!(task_logs[1].contains("after cancellation"));
+    }
+
+    #[tokio::test]
+    async fn test_shutdown_task_span_guard_released_early() {
+        let (logs, _default) = Logs::capture(tracing::Level::DEBUG);
+
+        let (tx, rx) = oneshot::channel::<()>();
+        let shutdown = Shutdown::new(rx);
+        let (done_tx, done_rx) = oneshot::channel::<()>();
+        shutdown.spawn_task_fn_named("worker", |guard| async move {
+            guard.cancelled().await;
+            drop(guard);
+            tokio::time::sleep(Duration::from_millis(50)).await;
+            done_tx.send(()).unwrap();
+        });
+        tx.send(()).unwrap();
+        shutdown.shutdown().await;
+
+        // the release is traced once the guard is dropped, not once the task completes
+        let logs_at_shutdown = logs.contents();
+        assert!(
+            logs_at_shutdown
+                .lines()
+                .any(|line| line.contains("task{name=worker}") && line.contains("released guard")),
+            "{}",
+            logs_at_shutdown
+        );
+        done_rx.await.unwrap();
+    }
+
+    #[cfg(feature = "leak-detection")]
+    #[tokio::test]
+    async fn test_shutdown_leak_detection() {
+        let (logs, _default) = Logs::capture(tracing::Level::WARN);
+
+        let (tx, rx) = oneshot::channel::<()>();
+        let shutdown = Shutdown::builder()
+            .with_signal(rx)
+            .with_leak_detection(Duration::from_millis(10))
+            .build();
+        // dropped guards are no longer tracked
+        drop(shutdown.guard());
+        let guard = shutdown.guard();
+        let _leaked = guard.clone();
+        drop(guard);
+        tx.send(()).unwrap();
+        shutdown
+            .shutdown_with_limit(Duration::from_millis(100))
+            .await
+            .unwrap_err();
+
+        let logs = logs.contents();
+        assert!(
+            logs.contains("1 guard(s) of 'main' still alive after 0.01s"),
+            "{}",
+            logs
+        );
+        assert!(logs.contains("guard cloned at:"), "{}", logs);
+        assert!(!logs.contains("guard created at:"), "{}", logs);
+    }
+
+    #[cfg(feature = "leak-detection")]
+    #[tokio::test]
+    async fn test_shutdown_leak_detection_created() {
+        let (logs, _default) = Logs::capture(tracing::Level::WARN);
+
+        let (tx, rx) = oneshot::channel::<()>();
+        let shutdown = Shutdown::builder()
+            .with_signal(rx)
+            .with_leak_detection(Duration::from_millis(10))
+            .build();
+        #[allow(clippy::mem_forget)]
+        std::mem::forget(shutdown.guard());
+        tx.send(()).unwrap();
+        shutdown
+            .shutdown_with_limit(Duration::from_millis(100))
+            .await
+            .unwrap_err();
+
+        let logs = logs.contents();
+        assert!(
+            logs.contains("1 guard(s) of 'main' still alive after 0.01s"),
+            "{}",
+            logs
+        );
+        assert!(logs.contains("guard created at:"), "{}", logs);
+        assert!(!logs.contains("guard cloned at:"), "{}", logs);
+    }
+
+    #[cfg(feature = "leak-detection")]
+    #[tokio::test]
+    async fn test_shutdown_leak_detection_phases() {
+        let (logs, _default) = Logs::capture(tracing::Level::WARN);
+
+        let (tx, rx) = oneshot::channel::<()>();
+        let shutdown = Shutdown::builder()
+            .with_signal(rx)
+            .with_phase("flush", Duration::from_secs(60))
+            .with_leak_detection(Duration::from_millis(10))
+            .build();
+        shutdown.spawn_task_fn(|guard| async move {
+            guard.cancelled().await;
+            tokio::time::sleep(Duration::from_millis(50)).await;
+        });
+        shutdown
+            .phase_guard("flush")
+            .unwrap()
+            .into_spawn_task_fn(|guard| async move {
+                guard.cancelled().await;
+                tokio::time::sleep(Duration::from_millis(50)).await;
+            });
+        tx.send(()).unwrap();
+        let report = shutdown.shutdown().await;
+        assert!(report.is_graceful());
+
+        // each drain only reports its own slow guard,
+        // and none of the guards held internally
+        let logs = logs.contents();
+        assert!(
+            logs.contains("1 guard(s) of 'main' still alive after 0.01s"),
+            "{}",
+            logs
+        );
+        assert!(
+            logs.contains("1 guard(s) of 'flush' still alive after 0.01s"),
+            "{}",
+            logs
+        );
+        // the guard of the task spawned by the shutdown is cloned from its internal guard,
+        // while the phase guard is created as it is handed out
+        assert_eq!(logs.matches("guard cloned at:").count(), 1, "{}", logs);
+        assert_eq!(logs.matches("guard created at:").count(), 1, "{}", logs);
+    }
+
+    #[tokio::test]
+    async fn test_shutdown_drain_progress() {
+        let (logs, _default) = Logs::capture(tracing::Level::INFO);
+
+        let (tx, rx) = oneshot::channel::<()>();
+        let shutdown = Shutdown::builder()
+            .with_signal(rx)
+            .with_drain_progress(Duration::from_millis(20))
+            .build();
+        shutdown.spawn_task_fn_named("worker", |guard| async move {
+            guard.cancelled().await;
+            tokio::time::sleep(Duration::from_millis(50)).await;
+        });
+        tx.send(()).unwrap();
+        shutdown.shutdown().await;
+
+        let logs = logs.contents();
+        let progress_logs: Vec<_> = logs
+            .lines()
+            .filter(|line| line.contains("still draining after"))
+            .collect();
+        assert!(!progress_logs.is_empty(), "{}", logs);
+        for line in progress_logs {
+            assert!(
+                line.contains("1 guard(s) remaining, still alive: worker"),
+                "{}",
+                line
+            );
+        }
+    }
+
+    #[tokio::test]
+    async fn test_shutdown_drain_progress_phases() {
+        let (logs, _default) = Logs::capture(tracing::Level::INFO);
+
+        let (tx, rx) = oneshot::channel::<()>();
+        let shutdown = Shutdown::builder()
+            .with_signal(rx)
+            .with_phase("flush", Duration::from_secs(60))
+            .with_drain_progress(Duration::from_millis(20))
+            .build();
+        shutdown.spawn_task_fn_named("worker", |guard| async move {
+            guard.cancelled().await;
+            tokio::time::sleep(Duration::from_millis(50)).await;
+        });
+        shutdown
+            .phase_guard("flush")
+            .unwrap()
+            .named("flusher")
+            .into_spawn_task_fn(|guard| async move {
+                guard.cancelled().await;
+                tokio::time::sleep(Duration::from_millis(50)).await;
+            });
+        tx.send(()).unwrap();
+        shutdown.shutdown().await;
+
+        // each drain only reports its own guards
+        let logs = logs.contents();
+        let progress_logs: Vec<_> = logs
+            .lines()
+            .filter(|line| line.contains("still draining after"))
+            .collect();
+        assert!(
+            progress_logs
+                .iter()
+                .any(|line| line.contains("::shutdown: still draining after")),
+            "{}",
+            logs
+        );
+        assert!(
+            progress_logs
+                .iter()
+                .any(|line| line.contains("::shutdown: phase 'flush': still draining after")),
+            "{}",
+            logs
+        );
+        for line in progress_logs {
+            let expected = if line.contains("phase 'flush'") {
+                "1 guard(s) remaining, still alive: flusher"
+            } else {
+                "1 guard(s) remaining, still alive: worker"
+            };
+            assert!(line.contains(expected), "{}", line);
+        }
+    }
+
+    #[tokio::test]
+    async fn test_shutdown_drain_progress_child() {
+        let (logs, _default) = Logs::capture(tracing::Level::INFO);
+
+        let shutdown = Shutdown::no_signal();
+        let parent_guard = shutdown.guard();
+        let build_child = |guard: &ShutdownGuard| {
+            Shutdown::builder()
+                .with_signal(std::future::ready(()))
+                .with_drain_progress(Duration::from_millis(20))
+                .with_parent(guard)
+                .build()
+        };
+        let children = [
+            build_child(&parent_guard),
+            build_child(&parent_guard.clone().named("worker")),
+        ];
+        drop(parent_guard);
+        for child in children {
+            child.spawn_task_fn_named("task", |guard| async move {
+                guard.cancelled().await;
+                tokio::time::sleep(Duration::from_millis(50)).await;
+            });
+            child.shutdown().await;
+        }
+        shutdown.shutdown().await;
+
+        // the guards of each child are reported under its own scope
+        let logs = logs.contents();
+        assert!(
+            logs.contains("::shutdown: scope 'main/child-1': still draining after"),
+            "{}",
+            logs
+        );
+        assert!(
+            logs.contains("::shutdown: scope 'main/worker': still draining after"),
+            "{}",
+            logs
+        );
+        assert!(
+            !logs.contains("::shutdown: still draining after"),
+            "{}",
+            logs
+        );
+    }
+
+    #[tokio::test]
+    async fn test_task_tracker_abort_while_spawning() {
+        let task_tracker = crate::task::TaskTracker::default();
+        // a task which is registered but not yet spawned
+        let token = task_tracker.register();
+        let (key, id) = token.key();
+        let abort_all = tokio::spawn({
+            let task_tracker = task_tracker.clone();
+            async move { task_tracker.abort_all().await }
+        });
+        tokio::task::yield_now().await;
+
+        let handle = tokio::spawn(async move {
+            let _token = token;
+            std::future::pending::<()>().await;
+        });
+        task_tracker.attach(key, id, handle.abort_handle());
+        let aborted = tokio::time::timeout(Duration::from_secs(1), abort_all)
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(aborted, 1);
+        assert!(handle.await.unwrap_err().is_cancelled());
+    }
+
+    #[tokio::test]
+    async fn test_task_tracker_abort_counts_aborted_tasks_only() {
+        let task_tracker = crate::task::TaskTracker::default();
+        let aborted_token = task_tracker.register();
+        let (key, id) = aborted_token.key();
+        let handle = tokio::spawn(async move {
+            let _token = aborted_token;
+            std::future::pending::<()>().await;
+        });
+        task_tracker.attach(key, id, handle.abort_handle());
+        // a task which completes on its own while the tasks are being aborted
+        let completed_token = task_tracker.register();
+        let (key, id) = completed_token.key();
+        let completed_handle = tokio::spawn(std::future::pending::<()>());
+        task_tracker.attach(key, id, completed_handle.abort_handle());
+
+        let abort_all = tokio::spawn({
+            let task_tracker = task_tracker.clone();
+            async move { task_tracker.abort_all().await }
+        });
+        tokio::task::yield_now().await;
+        completed_token.complete();
+        let aborted = tokio::time::timeout(Duration::from_secs(1), abort_all)
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(aborted, 1);
+        assert!(handle.await.unwrap_err().is_cancelled());
+
+        // the count is not cumulative across calls
+        assert_eq!(task_tracker.abort_all().await, 0);
+    }
+
+    #[tokio::test]
+    async fn test_shutdown_abort_on_timeout() {
+        let (tx, rx) = oneshot::channel::<()>();
+        let (dropped_tx, dropped_rx) = tokio::sync::mpsc::unbounded_channel::<()>();
+        let shutdown = Shutdown::builder()
+            .with_signal(rx)
+            .with_abort_on_timeout()
+            .build();
+        for _ in 0..2 {
+            let dropped_tx = dropped_tx.clone();
+            shutdown.spawn_task_fn(|guard| async move {
+                let _dropped_tx = dropped_tx;
+                guard.cancelled().await;
+                std::future::pending::<()>().await;
+            });
+        }
+        shutdown.spawn_task(async {});
+        drop(dropped_tx);
+        tx.send(()).unwrap();
+        let err = shutdown
+            .shutdown_with_limit(Duration::from_millis(10))
+            .await
+            .unwrap_err();
+        assert_eq!(err.report().aborted_tasks(), 2);
+        // all aborted tasks are dropped by the time the shutdown returns
+        assert!(dropped_rx.is_closed());
+    }
+
+    #[tokio::test]
+    async fn test_shutdown_abort_on_timeout_disabled() {
+        let (tx, rx) = oneshot::channel::<()>();
+        let shutdown = Shutdown::new(rx);
+        shutdown.spawn_task(std::future::pending::<()>());
+        tx.send(()).unwrap();
+        let err = shutdown
+            .shutdown_with_limit(Duration::from_millis(10))
+            .await
+            .unwrap_err();
+        assert_eq!(err.report().aborted_tasks(), 0);
+    }
+
     #[tokio::test]
     async fn test_shutdown_sixten_thousand_guards() {
         let (tx, rx) = oneshot::channel::<()>();
@@ -0,0 +1,153 @@
+//! The lifecycle of a [`Shutdown`], from running until the waiting
+//! for its guards has ended, observable using a watch channel.
+//!
+//! [`Shutdown`]: crate::Shutdown
+
+use std::{fmt, time::Duration};
+
+use tokio::sync::watch;
+
+use crate::sync::{Arc, AtomicUsize, Ordering};
+
+/// The lifecycle state of a [`Shutdown`].
+///
+/// It can be read and watched using [`Shutdown::lifecycle`] and [`Shutdown::watch_lifecycle`]
+/// (or their guard counterparts). The state only ever moves forward, with the exception
+/// of [`Lifecycle::Draining`] which is updated each time a guard is dropped.
+///
+/// [`Shutdown`]: crate::Shutdown
+/// [`Shutdown::lifecycle`]: crate::Shutdown::lifecycle
+/// [`Shutdown::watch_lifecycle`]: crate::Shutdown::watch_lifecycle
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum Lifecycle {
+    /// The shutdown signal has not been triggered yet.
+    Running,
+    /// The shutdown signal has been triggered (or the shutdown was triggered manually),
+    /// and the contained delay is being awaited ([`Duration::ZERO`] if there is no delay).
+    SignalReceived(Duration),
+    /// The delay has been awaited and the guards have been cancelled.
+    Cancelled,
+    /// The [`Shutdown`] is waiting for its guards to be dropped.
+    ///
+    /// [`Shutdown`]: crate::Shutdown
+    Draining {
+        /// The amount of guards which are still alive.
+        remaining_guards: usize,
+    },
+    /// The waiting for the guards of the [`Shutdown`] has ended,
+    /// and its phases are being run one after another.
+    ///
+    /// Only entered in case the [`Shutdown`] has phases,
+    /// see [`ShutdownBuilder::with_phase`].
+    ///
+    /// [`Shutdown`]: crate::Shutdown
+    /// [`ShutdownBuilder::with_phase`]: crate::ShutdownBuilder::with_phase
+    RunningPhases,
+    /// All guards have been dropped, including those of the phases.
+    Completed,
+    /// The time limit (or the budget of a phase) was reached
+    /// before all guards were dropped.
+    TimedOut,
+    /// The overwrite signal was triggered before all guards were dropped.
+    Overwritten,
+}
+
+impl Lifecycle {
+    /// Returns `true` if the waiting for the guards (and those of the phases) has ended,
+    /// either because they were all dropped, the time limit was reached
+    /// or the overwrite signal was triggered.
+    pub fn is_done(&self) -> bool {
+        matches!(
+            self,
+            Lifecycle::Completed | Lifecycle::TimedOut | Lifecycle::Overwritten
+        )
+    }
+
+    fn rank(&self) -> u8 {
+        match self {
+            Lifecycle::Running => 0,
+            Lifecycle::SignalReceived(_) => 1,
+            Lifecycle::Cancelled => 2,
+            Lifecycle::Draining { .. } => 3,
+            Lifecycle::RunningPhases => 4,
+            Lifecycle::Completed | Lifecycle::TimedOut | Lifecycle::Overwritten => 5,
+        }
+    }
+}
+
+impl fmt::Display for Lifecycle {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Lifecycle::Running => f.write_str("running"),
+            Lifecycle::SignalReceived(delay) => {
+                write!(f, "signal received (delay: {}s)", delay.as_secs_f64())
+            }
+            Lifecycle::Cancelled => f.write_str("cancelled"),
+            Lifecycle::Draining { remaining_guards } => {
+                write!(f, "draining ({} guard(s) remaining)", remaining_guards)
+            }
+            Lifecycle::RunningPhases => f.write_str("running phases"),
+            Lifecycle::Completed => f.write_str("completed"),
+            Lifecycle::TimedOut => f.write_str("timed out"),
+            Lifecycle::Overwritten => f.write_str("overwritten"),
+        }
+    }
+}
+
+/// The sending half of the [`Lifecycle`] watch channel,
+/// shared between all clones of a [`ShutdownTrigger`].
+///
+/// [`ShutdownTrigger`]: crate::ShutdownTrigger
+#[derive(Debug, Clone)]
+pub(crate) struct LifecycleSender {
+    tx: Arc<watch::Sender<Lifecycle>>,
+}
+
+impl Default for LifecycleSender {
+    fn default() -> Self {
+        let (tx, _) = watch::channel(Lifecycle::Running);
+        Self { tx: Arc::new(tx) }
+    }
+}
+
+impl LifecycleSender {
+    pub(crate) fn current(&self) -> Lifecycle {
+        *self.tx.borrow()
+    }
+
+    pub(crate) fn subscribe(&self) -> watch::Receiver<Lifecycle> {
+        self.tx.subscribe()
+    }
+
+    /// Moves the lifecycle forward to the given state,
+    /// ignored in case the lifecycle is already in a later state.
+    pub(crate) fn advance(&self, state: Lifecycle) {
+        self.tx.send_if_modified(|current| {
+            let forward = state.rank() > current.rank();
+            if forward {
+                tracing::debug!("::shutdown: lifecycle: {} -> {}", current, state);
+                *current = state;
+            }
+            forward
+        });
+    }
+
+    /// Updates the amount of remaining guards, only while draining.
+    ///
+    /// The amount is read while holding the lock of the watch channel,
+    /// such that concurrently dropped guards cannot leave a stale amount behind.
+    pub(crate) fn guard_dropped(&self, ref_count: &AtomicUsize) {
+        self.tx.send_if_modified(|current| match current {
+            Lifecycle::Draining {
+                remaining_guards: current_remaining,
+            } => {
+                let remaining_guards = ref_count.load(Ordering::SeqCst);
+                let changed = *current_remaining != remaining_guards;
+                *current_remaining = remaining_guards;
+                changed
+            }
+            _ => false,
+        });
+    }
+}
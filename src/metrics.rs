@@ -0,0 +1,52 @@
+//! Metrics of the guards, tasks and shutdowns, emitted using the [`metrics`] facade,
+//! such that they can be exported using any [`metrics`] recorder (e.g. Prometheus).
+//!
+//! The emitted metrics are documented in the "Metrics" section of the crate documentation.
+//!
+//! [`metrics`]: https://docs.rs/metrics
+
+use std::borrow::Cow;
+
+use crate::{DrainOutcome, ShutdownReport};
+
+const GUARDS: &str = "tokio_graceful_guards";
+const TASKS_SPAWNED: &str = "tokio_graceful_tasks_spawned_total";
+const SHUTDOWN_DELAY: &str = "tokio_graceful_shutdown_delay_seconds";
+const SHUTDOWN_DRAIN: &str = "tokio_graceful_shutdown_drain_seconds";
+const SHUTDOWNS: &str = "tokio_graceful_shutdowns_total";
+
+/// Records that a [`ShutdownGuard`] of the given scope was created,
+/// where the scope is either "main", the name of a phase or the scope of a child.
+///
+/// The guards held internally (by the [`Shutdown`] and its phases) are not recorded.
+///
+/// [`ShutdownGuard`]: crate::ShutdownGuard
+/// [`Shutdown`]: crate::Shutdown
+pub(crate) fn guard_created(scope: Cow<'static, str>) {
+    metrics::gauge!(GUARDS, "scope" => scope).increment(1.0);
+}
+
+/// Records that a [`ShutdownGuard`] of the given scope was dropped.
+///
+/// [`ShutdownGuard`]: crate::ShutdownGuard
+pub(crate) fn guard_dropped(scope: Cow<'static, str>) {
+    metrics::gauge!(GUARDS, "scope" => scope).decrement(1.0);
+}
+
+/// Records that a task was spawned using a guard.
+pub(crate) fn task_spawned() {
+    metrics::counter!(TASKS_SPAWNED).increment(1);
+}
+
+/// Records the timings and outcome of a completed shutdown.
+pub(crate) fn record_report(report: &ShutdownReport) {
+    metrics::histogram!(SHUTDOWN_DELAY).record(report.delay().as_secs_f64());
+    metrics::histogram!(SHUTDOWN_DRAIN).record(report.drain().as_secs_f64());
+    // a shutdown is only graceful in case its phases completed as well
+    let outcome = match report.final_outcome() {
+        DrainOutcome::Completed => "graceful",
+        DrainOutcome::TimedOut => "timeout",
+        DrainOutcome::Overwritten => "overwrite",
+    };
+    metrics::counter!(SHUTDOWNS, "outcome" => outcome).increment(1);
+}
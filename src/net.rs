@@ -0,0 +1,293 @@
+//! Graceful listeners, which stop accepting new connections
+//! once the shutdown signal is triggered and keep track of the
+//! connections which are still open, each linked to a [`ShutdownGuard`].
+
+use std::{
+    future::Future,
+    io,
+    pin::Pin,
+    task::{Context, Poll},
+};
+
+use pin_project_lite::pin_project;
+use tokio::io::{AsyncRead, AsyncWrite, ReadBuf};
+
+use crate::{
+    sync::{Arc, AtomicUsize, Ordering},
+    trigger::Receiver,
+    ShutdownGuard, WeakShutdownGuard,
+};
+
+/// A listener which can be wrapped by a [`GracefulListener`].
+///
+/// Implemented for [`tokio::net::TcpListener`] and (on Unix) [`tokio::net::UnixListener`].
+pub trait Listener {
+    /// The type of the accepted streams.
+    type Io;
+    /// The type of the address of the accepted peers.
+    type Addr;
+
+    /// Polls to accept a new incoming connection.
+    fn poll_accept(&mut self, cx: &mut Context<'_>) -> Poll<io::Result<(Self::Io, Self::Addr)>>;
+}
+
+impl Listener for tokio::net::TcpListener {
+    type Io = tokio::net::TcpStream;
+    type Addr = std::net::SocketAddr;
+
+    fn poll_accept(&mut self, cx: &mut Context<'_>) -> Poll<io::Result<(Self::Io, Self::Addr)>> {
+        tokio::net::TcpListener::poll_accept(self, cx)
+    }
+}
+
+#[cfg(unix)]
+impl Listener for tokio::net::UnixListener {
+    type Io = tokio::net::UnixStream;
+    type Addr = tokio::net::unix::SocketAddr;
+
+    fn poll_accept(&mut self, cx: &mut Context<'_>) -> Poll<io::Result<(Self::Io, Self::Addr)>> {
+        tokio::net::UnixListener::poll_accept(self, cx)
+    }
+}
+
+/// A wrapper around a [`Listener`] which stops accepting new connections
+/// once the shutdown signal is triggered (prior to the delay, if any, being awaited),
+/// and links each accepted stream to a [`ShutdownGuard`], such that the
+/// [`Shutdown`] waits for all accepted connections to be closed.
+///
+/// The listener itself does not hold a [`ShutdownGuard`], and thus
+/// does not prevent the [`Shutdown::shutdown`] future from completing.
+///
+/// ```no_run
+/// use tokio_graceful::{GracefulListener, Shutdown};
+///
+/// # async fn example() {
+/// let shutdown = Shutdown::default();
+/// let listener = tokio::net::TcpListener::bind("127.0.0.1:8080").await.unwrap();
+/// let mut listener = GracefulListener::new(listener, &shutdown.guard_weak());
+/// shutdown.spawn_task(async move {
+///     while let Some(result) = listener.accept().await {
+///         let Ok((mut stream, _)) = result else {
+///             continue;
+///         };
+///         tokio::spawn(async move {
+///             let (mut reader, mut writer) = tokio::io::split(&mut stream);
+///             let _ = tokio::io::copy(&mut reader, &mut writer).await;
+///         });
+///     }
+/// });
+/// shutdown.shutdown().await;
+/// # }
+/// ```
+///
+/// [`Shutdown`]: crate::Shutdown
+/// [`Shutdown::shutdown`]: crate::Shutdown::shutdown
+#[derive(Debug)]
+pub struct GracefulListener<L> {
+    listener: L,
+    guard: WeakShutdownGuard,
+    shutdown_signal: Receiver,
+    connections: ConnectionCounter,
+}
+
+impl<L: Listener> GracefulListener<L> {
+    /// Wraps the given listener, linking the accepted streams
+    /// to the [`Shutdown`] of the given guard.
+    ///
+    /// [`Shutdown`]: crate::Shutdown
+    pub fn new<G: AsRef<WeakShutdownGuard>>(listener: L, guard: &G) -> Self {
+        let guard = guard.as_ref().clone();
+        Self {
+            listener,
+            shutdown_signal: guard.shutdown_signal_rx(),
+            guard,
+            connections: ConnectionCounter::default(),
+        }
+    }
+
+    /// Accepts a new incoming connection, returning `None`
+    /// once the shutdown signal is triggered.
+    ///
+    /// # Cancel safety
+    ///
+    /// This method is cancel safe.
+    pub async fn accept(&mut self) -> Option<io::Result<(Connection<L::Io>, L::Addr)>> {
+        std::future::poll_fn(|cx| self.poll_accept(cx)).await
+    }
+
+    /// Polls to accept a new incoming connection, returning `Poll::Ready(None)`
+    /// once the shutdown signal is triggered.
+    pub fn poll_accept(
+        &mut self,
+        cx: &mut Context<'_>,
+    ) -> Poll<Option<io::Result<(Connection<L::Io>, L::Addr)>>> {
+        if Pin::new(&mut self.shutdown_signal).poll(cx).is_ready() {
+            tracing::trace!(
+                "::net: shutdown signal triggered: stop accepting: {} open connection(s)",
+                self.connections.open()
+            );
+            return Poll::Ready(None);
+        }
+        let (stream, addr) = match self.listener.poll_accept(cx) {
+            Poll::Ready(Ok(accepted)) => accepted,
+            Poll::Ready(Err(err)) => return Poll::Ready(Some(Err(err))),
+            Poll::Pending => return Poll::Pending,
+        };
+        let connection = Connection::new(stream, self.guard.clone().upgrade(), &self.connections);
+        Poll::Ready(Some(Ok((connection, addr))))
+    }
+
+    /// Returns a [`ConnectionCounter`] for the connections accepted by this listener,
+    /// which can be used to track the drain progress of the connections,
+    /// also after this listener has been dropped.
+    pub fn connections(&self) -> ConnectionCounter {
+        self.connections.clone()
+    }
+
+    /// Returns the amount of connections accepted by this listener
+    /// which are still open.
+    pub fn open_connections(&self) -> usize {
+        self.connections.open()
+    }
+
+    /// Returns a reference to the wrapped listener.
+    pub fn get_ref(&self) -> &L {
+        &self.listener
+    }
+
+    /// Consumes this wrapper, returning the wrapped listener.
+    pub fn into_inner(self) -> L {
+        self.listener
+    }
+}
+
+/// Counts the connections accepted by a [`GracefulListener`],
+/// obtained using [`GracefulListener::connections`].
+///
+/// It can be cloned cheaply and remains usable after the listener is dropped,
+/// e.g. to report the drain progress of the open connections during a shutdown.
+#[derive(Debug, Clone, Default)]
+pub struct ConnectionCounter {
+    state: Arc<ConnectionState>,
+}
+
+#[derive(Debug, Default)]
+struct ConnectionState {
+    open: AtomicUsize,
+    accepted: AtomicUsize,
+}
+
+impl ConnectionCounter {
+    /// Returns the amount of accepted connections which are still open.
+    pub fn open(&self) -> usize {
+        self.state.open.load(Ordering::SeqCst)
+    }
+
+    /// Returns the total amount of accepted connections.
+    pub fn accepted(&self) -> usize {
+        self.state.accepted.load(Ordering::SeqCst)
+    }
+}
+
+pin_project! {
+    /// A stream accepted by a [`GracefulListener`], linked to a [`ShutdownGuard`]
+    /// which is dropped together with the stream.
+    ///
+    /// It implements [`AsyncRead`] and [`AsyncWrite`] when the wrapped stream does.
+    #[derive(Debug)]
+    pub struct Connection<S> {
+        #[pin]
+        stream: S,
+        token: ConnectionToken,
+    }
+}
+
+impl<S> Connection<S> {
+    fn new(stream: S, guard: ShutdownGuard, counter: &ConnectionCounter) -> Self {
+        counter.state.accepted.fetch_add(1, Ordering::SeqCst);
+        let open = counter.state.open.fetch_add(1, Ordering::SeqCst) + 1;
+        tracing::trace!("::net: connection accepted: {} open connection(s)", open);
+        Self {
+            stream,
+            token: ConnectionToken {
+                counter: counter.clone(),
+                guard,
+            },
+        }
+    }
+
+    /// Returns the [`ShutdownGuard`] linked to this connection.
+    pub fn guard(&self) -> &ShutdownGuard {
+        &self.token.guard
+    }
+
+    /// Returns a reference to the wrapped stream.
+    pub fn get_ref(&self) -> &S {
+        &self.stream
+    }
+
+    /// Returns a mutable reference to the wrapped stream.
+    pub fn get_mut(&mut self) -> &mut S {
+        &mut self.stream
+    }
+}
+
+#[derive(Debug)]
+struct ConnectionToken {
+    counter: ConnectionCounter,
+    guard: ShutdownGuard,
+}
+
+impl Drop for ConnectionToken {
+    fn drop(&mut self) {
+        let open = self.counter.state.open.fetch_sub(1, Ordering::SeqCst) - 1;
+        if self.guard.is_shutdown_signal_triggered() {
+            tracing::debug!(
+                "::net: connection closed: {} open connection(s) remaining",
+                open
+            );
+        } else {
+            tracing::trace!("::net: connection closed: {} open connection(s)", open);
+        }
+    }
+}
+
+impl<S: AsyncRead> AsyncRead for Connection<S> {
+    fn poll_read(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &mut ReadBuf<'_>,
+    ) -> Poll<io::Result<()>> {
+        self.project().stream.poll_read(cx, buf)
+    }
+}
+
+impl<S: AsyncWrite> AsyncWrite for Connection<S> {
+    fn poll_write(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &[u8],
+    ) -> Poll<io::Result<usize>> {
+        self.project().stream.poll_write(cx, buf)
+    }
+
+    fn poll_flush(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        self.project().stream.poll_flush(cx)
+    }
+
+    fn poll_shutdown(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        self.project().stream.poll_shutdown(cx)
+    }
+
+    fn poll_write_vectored(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        bufs: &[io::IoSlice<'_>],
+    ) -> Poll<io::Result<usize>> {
+        self.project().stream.poll_write_vectored(cx, bufs)
+    }
+
+    fn is_write_vectored(&self) -> bool {
+        self.stream.is_write_vectored()
+    }
+}
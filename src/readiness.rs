@@ -0,0 +1,266 @@
+//! Readiness of a [`Shutdown`], driven by its lifecycle,
+//! such that orchestrators and load balancers can be informed
+//! that the service is going away during the delay of the shutdown.
+//!
+//! [`Shutdown`]: crate::Shutdown
+
+use std::fmt;
+
+use tokio::sync::watch;
+
+use crate::sync::Arc;
+
+/// The readiness state of a [`Shutdown`], see [`Readiness`] for more information.
+///
+/// The states are ordered, a [`Readiness`] only ever moves forward.
+///
+/// [`Shutdown`]: crate::Shutdown
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[non_exhaustive]
+pub enum ReadinessState {
+    /// The service is starting and not yet ready to handle requests.
+    Starting,
+    /// The service is ready to handle requests,
+    /// see [`Readiness::set_ready`].
+    Ready,
+    /// The shutdown signal is triggered (or the shutdown is cancelled),
+    /// the service is draining and should no longer receive new requests.
+    Draining,
+    /// All guards of the shutdown, including those of its phases, have been dropped
+    /// (or the waiting for them was stopped due to a timeout or overwrite signal).
+    Stopped,
+}
+
+impl ReadinessState {
+    /// Returns the name of the state, in lowercase.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ReadinessState::Starting => "starting",
+            ReadinessState::Ready => "ready",
+            ReadinessState::Draining => "draining",
+            ReadinessState::Stopped => "stopped",
+        }
+    }
+}
+
+impl fmt::Display for ReadinessState {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// A [`Readiness`] publishes the readiness of a [`Shutdown`],
+/// moving from [`ReadinessState::Starting`] to [`ReadinessState::Ready`]
+/// once [`Readiness::set_ready`] is called, to [`ReadinessState::Draining`]
+/// once the shutdown signal is triggered and to [`ReadinessState::Stopped`]
+/// once all guards of the [`Shutdown`] have been dropped.
+///
+/// It can be obtained using [`Shutdown::readiness`], [`ShutdownGuard::readiness`]
+/// or [`WeakShutdownGuard::readiness`], and can be cloned cheaply.
+///
+/// ```
+/// use tokio_graceful::{ReadinessState, Shutdown};
+///
+/// # async fn example() {
+/// let shutdown = Shutdown::builder()
+///     .with_signal(std::future::pending::<()>())
+///     .build();
+/// let readiness = shutdown.readiness();
+/// assert_eq!(readiness.state(), ReadinessState::Starting);
+/// readiness.set_ready();
+/// assert!(readiness.is_ready());
+/// # }
+/// ```
+///
+/// [`Shutdown`]: crate::Shutdown
+/// [`Shutdown::readiness`]: crate::Shutdown::readiness
+/// [`ShutdownGuard::readiness`]: crate::ShutdownGuard::readiness
+/// [`WeakShutdownGuard::readiness`]: crate::WeakShutdownGuard::readiness
+#[derive(Debug, Clone)]
+pub struct Readiness {
+    tx: Arc<watch::Sender<ReadinessState>>,
+    #[cfg(all(feature = "systemd", unix))]
+    systemd_notify: bool,
+}
+
+impl Default for Readiness {
+    fn default() -> Self {
+        let (tx, _) = watch::channel(ReadinessState::Starting);
+        Self {
+            tx: Arc::new(tx),
+            #[cfg(all(feature = "systemd", unix))]
+            systemd_notify: false,
+        }
+    }
+}
+
+impl Readiness {
+    /// Returns the current readiness state.
+    pub fn state(&self) -> ReadinessState {
+        *self.tx.borrow()
+    }
+
+    /// Returns `true` if the current readiness state is [`ReadinessState::Ready`].
+    pub fn is_ready(&self) -> bool {
+        self.state() == ReadinessState::Ready
+    }
+
+    /// Marks the service as ready, moving from [`ReadinessState::Starting`]
+    /// to [`ReadinessState::Ready`].
+    ///
+    /// This has no effect once the service is draining or stopped.
+    pub fn set_ready(&self) {
+        self.advance(ReadinessState::Ready);
+    }
+
+    /// Returns a [`watch::Receiver`] which can be used to (asynchronously)
+    /// watch the readiness state for changes.
+    pub fn watch(&self) -> watch::Receiver<ReadinessState> {
+        self.tx.subscribe()
+    }
+
+    /// Notifies the systemd service manager of each change of readiness state,
+    /// see [`ShutdownBuilder::with_systemd_notify`] for more information.
+    ///
+    /// [`ShutdownBuilder::with_systemd_notify`]: crate::ShutdownBuilder::with_systemd_notify
+    #[cfg(all(feature = "systemd", unix))]
+    pub(crate) fn with_systemd_notify(mut self) -> Self {
+        self.systemd_notify = true;
+        self
+    }
+
+    #[cfg(all(feature = "systemd", unix))]
+    pub(crate) fn systemd_notify(&self) -> bool {
+        self.systemd_notify
+    }
+
+    /// Moves the readiness forward to the given state,
+    /// ignored in case the readiness is already in that or a later state.
+    pub(crate) fn advance(&self, state: ReadinessState) {
+        let changed = self.tx.send_if_modified(|current| {
+            if *current < state {
+                tracing::debug!("::readiness: {} -> {}", current, state);
+                *current = state;
+                true
+            } else {
+                false
+            }
+        });
+        #[cfg(all(feature = "systemd", unix))]
+        if changed && self.systemd_notify {
+            crate::systemd::notify_readiness(state);
+        }
+        #[cfg(not(all(feature = "systemd", unix)))]
+        let _ = changed;
+    }
+}
+
+/// The time a connection to the health endpoint (see [`serve_health`]) is given
+/// to send its request and receive the response, before it is closed.
+#[cfg(feature = "health")]
+const HEALTH_CONNECTION_TIMEOUT: std::time::Duration = std::time::Duration::from_secs(5);
+
+/// Serves a minimal HTTP health endpoint on the given listener,
+/// until the given [`Readiness`] is [`ReadinessState::Stopped`].
+///
+/// - `GET /readyz` responds with `200 OK` when ready, and `503 Service Unavailable` otherwise;
+/// - `GET /livez` responds with `200 OK` until stopped;
+/// - any other path responds with `404 Not Found`.
+///
+/// The body of each response contains the current readiness state.
+/// Connections which do not complete their request and response within
+/// 5 seconds are closed, such that idle probes
+/// cannot keep tasks alive during the shutdown.
+///
+/// This future does not hold a [`ShutdownGuard`], and is therefore best spawned using
+/// [`tokio::spawn`] instead of using a guard, such that it does not prevent the
+/// [`Shutdown`] from completing.
+///
+/// ```no_run
+/// use tokio_graceful::Shutdown;
+///
+/// # async fn example() {
+/// let shutdown = Shutdown::default();
+/// let listener = tokio::net::TcpListener::bind("127.0.0.1:8081").await.unwrap();
+/// tokio::spawn(tokio_graceful::serve_health(listener, shutdown.readiness()));
+/// shutdown.readiness().set_ready();
+/// shutdown.shutdown().await;
+/// # }
+/// ```
+///
+/// [`ShutdownGuard`]: crate::ShutdownGuard
+/// [`Shutdown`]: crate::Shutdown
+#[cfg(feature = "health")]
+pub async fn serve_health(listener: tokio::net::TcpListener, readiness: Readiness) {
+    let mut watch = readiness.watch();
+    loop {
+        let (mut stream, _) = tokio::select! {
+            _ = watch.wait_for(|state| *state == ReadinessState::Stopped) => {
+                tracing::trace!("::readiness: stopped: stop serving health endpoint");
+                return;
+            }
+            result = listener.accept() => match result {
+                Ok(accepted) => accepted,
+                Err(err) => {
+                    tracing::debug!("::readiness: health endpoint: accept error: {}", err);
+                    continue;
+                }
+            },
+        };
+        let readiness = readiness.clone();
+        tokio::spawn(async move {
+            match tokio::time::timeout(
+                HEALTH_CONNECTION_TIMEOUT,
+                respond_health(&mut stream, &readiness),
+            )
+            .await
+            {
+                Ok(Ok(())) => (),
+                Ok(Err(err)) => {
+                    tracing::debug!("::readiness: health endpoint: connection error: {}", err)
+                }
+                Err(_) => tracing::debug!("::readiness: health endpoint: connection timed out"),
+            }
+        });
+    }
+}
+
+#[cfg(feature = "health")]
+async fn respond_health(
+    stream: &mut tokio::net::TcpStream,
+    readiness: &Readiness,
+) -> std::io::Result<()> {
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+    let mut buf = [0; 1024];
+    let mut len = 0;
+    while !buf[..len].windows(4).any(|window| window == b"\r\n\r\n") && len < buf.len() {
+        let n = stream.read(&mut buf[len..]).await?;
+        if n == 0 {
+            break;
+        }
+        len += n;
+    }
+
+    let mut request_line = buf[..len].split(|b| *b == b' ');
+    let method = request_line.next().unwrap_or_default();
+    let path = request_line.next().unwrap_or_default();
+
+    let state = readiness.state();
+    let status = match (method, path) {
+        (b"GET", b"/readyz") if state == ReadinessState::Ready => "200 OK",
+        (b"GET", b"/readyz") => "503 Service Unavailable",
+        (b"GET", b"/livez") if state < ReadinessState::Stopped => "200 OK",
+        (b"GET", b"/livez") => "503 Service Unavailable",
+        _ => "404 Not Found",
+    };
+    let body = state.as_str();
+    let response = format!(
+        "HTTP/1.1 {}\r\ncontent-type: text/plain\r\ncontent-length: {}\r\nconnection: close\r\n\r\n{}",
+        status,
+        body.len(),
+        body
+    );
+    stream.write_all(response.as_bytes()).await?;
+    stream.shutdown().await
+}
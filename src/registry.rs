@@ -0,0 +1,160 @@
+//! A registry of named [`ShutdownGuard`]s,
+//! used to be able to tell which guards are still alive,
+//! e.g. when a shutdown is taking longer than expected.
+//!
+//! Only named guards are registered, such that the creation
+//! of regular (unnamed) guards remains lock-free.
+//!
+//! [`ShutdownGuard`]: crate::ShutdownGuard
+
+use std::{borrow::Cow, fmt, panic::Location, time::Instant};
+
+use slab::Slab;
+
+use crate::sync::{Arc, AtomicUsize, Mutex, Ordering};
+
+/// Information about a named [`ShutdownGuard`] which is still alive.
+///
+/// See [`Shutdown::live_guards`] for more information.
+///
+/// [`ShutdownGuard`]: crate::ShutdownGuard
+/// [`Shutdown::live_guards`]: crate::Shutdown::live_guards
+#[derive(Debug, Clone)]
+pub struct GuardInfo {
+    name: Cow<'static, str>,
+    location: &'static Location<'static>,
+    created: Instant,
+}
+
+impl GuardInfo {
+    /// Returns the name of the guard.
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Returns the location in the source code where the guard was created.
+    pub fn location(&self) -> &'static Location<'static> {
+        self.location
+    }
+
+    /// Returns the time elapsed since the guard was created.
+    pub fn age(&self) -> std::time::Duration {
+        self.created.elapsed()
+    }
+}
+
+impl fmt::Display for GuardInfo {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} (created at {}, {}s ago)",
+            self.name,
+            self.location,
+            self.age().as_secs_f64()
+        )
+    }
+}
+
+#[derive(Debug, Clone)]
+pub(crate) struct Registry {
+    guards: Arc<Mutex<Slab<GuardInfo>>>,
+    scope: Cow<'static, str>,
+    children: Arc<AtomicUsize>,
+}
+
+impl Default for Registry {
+    fn default() -> Self {
+        Self::new(Cow::Borrowed("main"))
+    }
+}
+
+impl Registry {
+    /// Creates a new registry for the guards of the given scope,
+    /// which is either "main" (the [`Shutdown`] itself), the scope of a child
+    /// [`Shutdown`] (see [`Registry::child_scope`]) or the name of a phase.
+    ///
+    /// [`Shutdown`]: crate::Shutdown
+    pub(crate) fn new(scope: Cow<'static, str>) -> Self {
+        Self {
+            guards: Default::default(),
+            scope,
+            children: Default::default(),
+        }
+    }
+
+    /// Returns the scope of the guards of this registry.
+    pub(crate) fn scope(&self) -> Cow<'static, str> {
+        self.scope.clone()
+    }
+
+    /// Returns whether this is the registry of a root [`Shutdown`],
+    /// as opposed to the one of a child or phase.
+    ///
+    /// [`Shutdown`]: crate::Shutdown
+    pub(crate) fn is_main(&self) -> bool {
+        self.scope == "main"
+    }
+
+    /// Returns the scope of a new child [`Shutdown`] derived from a guard of this registry,
+    /// nested within the scope of this registry and named after the guard (if named),
+    /// or numbered otherwise, e.g. `main/child-1`.
+    ///
+    /// [`Shutdown`]: crate::Shutdown
+    pub(crate) fn child_scope(&self, name: Option<&str>) -> Cow<'static, str> {
+        match name {
+            Some(name) => Cow::Owned(format!("{}/{}", self.scope, name)),
+            None => {
+                let id = self.children.fetch_add(1, Ordering::SeqCst) + 1;
+                Cow::Owned(format!("{}/child-{}", self.scope, id))
+            }
+        }
+    }
+
+    /// Registers a named guard, returning the key
+    /// which is to be used to remove it once the guard is dropped.
+    pub(crate) fn insert(
+        &self,
+        name: Cow<'static, str>,
+        location: &'static Location<'static>,
+    ) -> usize {
+        let key = self.guards.lock().unwrap().insert(GuardInfo {
+            name,
+            location,
+            created: Instant::now(),
+        });
+        tracing::trace!("registry: insert guard for key: {}", key);
+        key
+    }
+
+    /// Returns the name of the guard registered with the given key.
+    pub(crate) fn name(&self, key: usize) -> Option<Cow<'static, str>> {
+        self.guards
+            .lock()
+            .unwrap()
+            .get(key)
+            .map(|info| info.name.clone())
+    }
+
+    /// Removes the guard registered with the given key, returning its name.
+    pub(crate) fn remove(&self, key: usize) -> Option<Cow<'static, str>> {
+        tracing::trace!("registry: remove guard for key: {}", key);
+        self.guards
+            .lock()
+            .unwrap()
+            .try_remove(key)
+            .map(|info| info.name)
+    }
+
+    /// Returns the info of all registered guards, oldest first.
+    pub(crate) fn snapshot(&self) -> Vec<GuardInfo> {
+        let mut guards: Vec<_> = self
+            .guards
+            .lock()
+            .unwrap()
+            .iter()
+            .map(|(_, info)| info.clone())
+            .collect();
+        guards.sort_by_key(|info| info.created);
+        guards
+    }
+}
@@ -0,0 +1,162 @@
+//! Reload facility, living next to the shutdown of a [`Shutdown`],
+//! allowing tasks to be notified each time a reload is requested,
+//! e.g. to reload their configuration upon receiving a SIGHUP signal.
+//!
+//! [`Shutdown`]: crate::Shutdown
+
+use crate::{
+    trigger::{generation_trigger, GenerationReceiver, GenerationSender},
+    WeakShutdownGuard,
+};
+
+/// A [`ReloadTrigger`] can be used to request a reload
+/// from all [`ReloadGuard`]s linked to the same [`Shutdown`].
+///
+/// It can be obtained using [`Shutdown::reload_trigger`],
+/// [`ShutdownGuard::reload_trigger`] or [`WeakShutdownGuard::reload_trigger`],
+/// and can be cloned cheaply.
+///
+/// [`Shutdown`]: crate::Shutdown
+/// [`Shutdown::reload_trigger`]: crate::Shutdown::reload_trigger
+/// [`ShutdownGuard::reload_trigger`]: crate::ShutdownGuard::reload_trigger
+#[derive(Debug, Clone)]
+pub struct ReloadTrigger {
+    tx: GenerationSender,
+}
+
+impl Default for ReloadTrigger {
+    fn default() -> Self {
+        Self {
+            tx: generation_trigger(),
+        }
+    }
+}
+
+impl ReloadTrigger {
+    /// Requests a reload from all [`ReloadGuard`]s
+    /// linked to the same [`Shutdown`].
+    ///
+    /// Multiple requests made before a [`ReloadGuard`] got
+    /// to observe them are coalesced into a single reload for that guard.
+    ///
+    /// # Panics
+    ///
+    /// This method can panic if the internal mutex is poisoned.
+    ///
+    /// [`Shutdown`]: crate::Shutdown
+    pub fn trigger(&self) {
+        tracing::debug!("::reload: reload requested");
+        self.tx.trigger();
+    }
+
+    pub(crate) fn subscribe(&self) -> GenerationReceiver {
+        self.tx.subscribe()
+    }
+}
+
+/// A [`ReloadGuard`] can be used to wait for reloads to be requested,
+/// until the [`Shutdown`] it is linked to is cancelled.
+///
+/// A reload can be requested using a [`ReloadTrigger`], or on Unix by one of the
+/// signals configured using `ShutdownBuilder::with_reload_signal`.
+/// Only reloads requested after the creation of the guard are observed.
+///
+/// In contrast to a [`ShutdownGuard`] it does not
+/// prevent the [`Shutdown::shutdown`] future from completing.
+///
+/// ```no_run
+/// use tokio_graceful::Shutdown;
+///
+/// # async fn example() {
+/// let shutdown = Shutdown::default();
+/// let mut reload_guard = shutdown.reload_guard();
+/// shutdown.spawn_task(async move {
+///     while reload_guard.reload_requested().await {
+///         // reload the configuration
+///     }
+/// });
+/// shutdown.shutdown().await;
+/// # }
+/// ```
+///
+/// [`Shutdown`]: crate::Shutdown
+/// [`Shutdown::shutdown`]: crate::Shutdown::shutdown
+/// [`ShutdownGuard`]: crate::ShutdownGuard
+#[derive(Debug, Clone)]
+pub struct ReloadGuard {
+    reload_rx: GenerationReceiver,
+    weak_guard: WeakShutdownGuard,
+}
+
+impl ReloadGuard {
+    pub(crate) fn new(weak_guard: WeakShutdownGuard) -> Self {
+        Self {
+            reload_rx: weak_guard.reload_trigger.subscribe(),
+            weak_guard,
+        }
+    }
+
+    /// Waits for a reload to be requested, returning `true` when one was requested,
+    /// or `false` once the [`Shutdown`] is cancelled, in which case no
+    /// further reloads are to be expected.
+    ///
+    /// Multiple reloads requested in between two calls are coalesced into one.
+    ///
+    /// # Cancel safety
+    ///
+    /// This method is cancel safe.
+    ///
+    /// # Panics
+    ///
+    /// This method panics if the iternal mutex
+    /// is poisoned while being used.
+    ///
+    /// [`Shutdown`]: crate::Shutdown
+    pub async fn reload_requested(&mut self) -> bool {
+        tokio::select! {
+            biased;
+            _ = self.weak_guard.cancelled() => false,
+            _ = self.reload_rx.changed() => true,
+        }
+    }
+}
+
+/// Spawns a task which requests a reload each time one of the given signals is received,
+/// until the [`Shutdown`] linked to the given guard is cancelled.
+///
+/// The signals are registered prior to spawning the task,
+/// such that no signal is missed (or handled by the default handler).
+///
+/// [`Shutdown`]: crate::Shutdown
+#[cfg(all(not(loom), unix))]
+pub(crate) fn spawn_reload_signals(
+    kinds: &[tokio::signal::unix::SignalKind],
+    weak_guard: WeakShutdownGuard,
+) {
+    if kinds.is_empty() {
+        return;
+    }
+    let mut signals = match crate::signal::UnixSignals::new(kinds) {
+        Ok(signals) => signals,
+        Err(err) => {
+            tracing::warn!("::reload: failed to register reload signal: {}", err);
+            return;
+        }
+    };
+    let reload_trigger = weak_guard.reload_trigger();
+    crate::sync::spawn(async move {
+        loop {
+            tokio::select! {
+                biased;
+                _ = weak_guard.cancelled() => {
+                    tracing::trace!("::reload: cancelled: stop listening for reload signals");
+                    break;
+                }
+                signal = signals.recv() => {
+                    tracing::info!("::reload: {} received", signal);
+                    reload_trigger.trigger();
+                }
+            }
+        }
+    });
+}
@@ -0,0 +1,296 @@
+use std::{borrow::Cow, fmt, time::Duration};
+
+use crate::{CriticalTaskExit, GuardInfo, OsSignal, Timeline};
+
+/// The report of a [`Shutdown`], resolved by [`Shutdown::shutdown`]
+/// and [`Shutdown::shutdown_with_limit`].
+///
+/// [`Shutdown`]: crate::Shutdown
+/// [`Shutdown::shutdown`]: crate::Shutdown::shutdown
+/// [`Shutdown::shutdown_with_limit`]: crate::Shutdown::shutdown_with_limit
+#[derive(Debug, Clone)]
+pub struct ShutdownReport {
+    reason: Option<ShutdownReason>,
+    delay: Duration,
+    drain: Duration,
+    overwrite: Option<Duration>,
+    outcome: DrainOutcome,
+    live_guards: Vec<GuardInfo>,
+    aborted_tasks: usize,
+    phases: Vec<PhaseReport>,
+    timeline: Option<Timeline>,
+}
+
+/// The stats of the drain stage of a [`Shutdown`],
+/// used to create its [`ShutdownReport`].
+///
+/// [`Shutdown`]: crate::Shutdown
+#[derive(Debug)]
+pub(crate) struct DrainStats {
+    pub(crate) elapsed: Duration,
+    pub(crate) outcome: DrainOutcome,
+    pub(crate) live_guards: Vec<GuardInfo>,
+    pub(crate) aborted_tasks: usize,
+}
+
+impl ShutdownReport {
+    pub(crate) fn new(
+        reason: Option<ShutdownReason>,
+        delay: Duration,
+        overwrite: Option<Duration>,
+        drain: DrainStats,
+        phases: Vec<PhaseReport>,
+    ) -> Self {
+        Self {
+            reason,
+            delay,
+            drain: drain.elapsed,
+            overwrite,
+            outcome: drain.outcome,
+            live_guards: drain.live_guards,
+            aborted_tasks: drain.aborted_tasks,
+            phases,
+            timeline: None,
+        }
+    }
+
+    /// Attaches the [`Timeline`] recorded for the shutdown to the report.
+    pub(crate) fn with_timeline(mut self, timeline: Timeline) -> Self {
+        self.timeline = Some(timeline);
+        self
+    }
+
+    /// Returns the reason why the shutdown was triggered.
+    ///
+    /// This is `None` for a [`Shutdown`] without a signal.
+    ///
+    /// [`Shutdown`]: crate::Shutdown
+    pub fn reason(&self) -> Option<&ShutdownReason> {
+        self.reason.as_ref()
+    }
+
+    /// Returns the time between the shutdown being triggered
+    /// and the guards being cancelled, which is the delay
+    /// (if any) configured for the [`Shutdown`].
+    ///
+    /// [`Shutdown`]: crate::Shutdown
+    pub fn delay(&self) -> Duration {
+        self.delay
+    }
+
+    /// Returns the time it took for the [`Shutdown`] to
+    /// wait for all its [`ShutdownGuard`]s to be dropped,
+    /// not including the time spent in its phases.
+    ///
+    /// [`Shutdown`]: crate::Shutdown
+    /// [`ShutdownGuard`]: crate::ShutdownGuard
+    pub fn drain(&self) -> Duration {
+        self.drain
+    }
+
+    /// Returns the time between the shutdown being triggered
+    /// and the overwrite signal being triggered,
+    /// or `None` if the overwrite signal was not triggered.
+    pub fn overwrite(&self) -> Option<Duration> {
+        self.overwrite
+    }
+
+    /// Returns how the waiting for all [`ShutdownGuard`]s to be dropped ended,
+    /// not including the phases, which have their own outcome.
+    ///
+    /// Use [`ShutdownReport::is_graceful`] to check whether
+    /// the phases completed as well.
+    ///
+    /// [`ShutdownGuard`]: crate::ShutdownGuard
+    pub fn outcome(&self) -> DrainOutcome {
+        self.outcome
+    }
+
+    /// Returns whether the shutdown was graceful, meaning that all [`ShutdownGuard`]s
+    /// were dropped in time, both those of the [`Shutdown`] and those of its phases.
+    ///
+    /// [`Shutdown`]: crate::Shutdown
+    /// [`ShutdownGuard`]: crate::ShutdownGuard
+    pub fn is_graceful(&self) -> bool {
+        self.final_outcome() == DrainOutcome::Completed
+    }
+
+    /// Returns the outcome of the shutdown as a whole, which is the outcome of the
+    /// waiting for the guards in case it did not complete, or otherwise
+    /// the outcome of the first phase which did not complete (if any).
+    pub(crate) fn final_outcome(&self) -> DrainOutcome {
+        self.phases.iter().map(|phase| phase.outcome).fold(
+            self.outcome,
+            |outcome, phase_outcome| match outcome {
+                DrainOutcome::Completed => phase_outcome,
+                _ => outcome,
+            },
+        )
+    }
+
+    /// Returns the info of the named [`ShutdownGuard`]s which were
+    /// still alive when the waiting for all guards to be dropped ended,
+    /// not including those of its phases (see [`PhaseReport::live_guards`]).
+    ///
+    /// This is empty in case the [`DrainOutcome`] is [`DrainOutcome::Completed`].
+    /// See [`ShutdownGuard::named`] for more information on how to name a guard.
+    ///
+    /// [`ShutdownGuard`]: crate::ShutdownGuard
+    /// [`ShutdownGuard::named`]: crate::ShutdownGuard::named
+    pub fn live_guards(&self) -> &[GuardInfo] {
+        &self.live_guards
+    }
+
+    /// Returns the amount of tasks which were aborted
+    /// once the time limit was reached.
+    ///
+    /// This is always zero unless the [`Shutdown`] was built using
+    /// [`ShutdownBuilder::with_abort_on_timeout`].
+    ///
+    /// [`Shutdown`]: crate::Shutdown
+    /// [`ShutdownBuilder::with_abort_on_timeout`]: crate::ShutdownBuilder::with_abort_on_timeout
+    pub fn aborted_tasks(&self) -> usize {
+        self.aborted_tasks
+    }
+
+    /// Returns the reports of the phases that were run,
+    /// in the order they were run in.
+    pub fn phases(&self) -> &[PhaseReport] {
+        &self.phases
+    }
+
+    /// Returns the [`Timeline`] of the shutdown, in case it was
+    /// recorded using [`ShutdownBuilder::with_timeline`].
+    ///
+    /// [`ShutdownBuilder::with_timeline`]: crate::ShutdownBuilder::with_timeline
+    pub fn timeline(&self) -> Option<&Timeline> {
+        self.timeline.as_ref()
+    }
+}
+
+/// The report of a single shutdown phase,
+/// configured using [`ShutdownBuilder::with_phase`].
+///
+/// [`ShutdownBuilder::with_phase`]: crate::ShutdownBuilder::with_phase
+#[derive(Debug, Clone)]
+pub struct PhaseReport {
+    name: Cow<'static, str>,
+    elapsed: Duration,
+    outcome: DrainOutcome,
+    live_guards: Vec<GuardInfo>,
+    aborted_tasks: usize,
+}
+
+impl PhaseReport {
+    pub(crate) fn new(
+        name: Cow<'static, str>,
+        elapsed: Duration,
+        outcome: DrainOutcome,
+        live_guards: Vec<GuardInfo>,
+        aborted_tasks: usize,
+    ) -> Self {
+        Self {
+            name,
+            elapsed,
+            outcome,
+            live_guards,
+            aborted_tasks,
+        }
+    }
+
+    /// Returns the name of the phase.
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Returns the time it took for the phase to complete.
+    pub fn elapsed(&self) -> Duration {
+        self.elapsed
+    }
+
+    /// Returns how the phase ended.
+    pub fn outcome(&self) -> DrainOutcome {
+        self.outcome
+    }
+
+    /// Returns the info of the named guards of the phase which were
+    /// still alive when the waiting for its guards to be dropped ended.
+    ///
+    /// See [`ShutdownReport::live_guards`] for more information.
+    pub fn live_guards(&self) -> &[GuardInfo] {
+        &self.live_guards
+    }
+
+    /// Returns the amount of tasks of the phase which were aborted
+    /// once its time budget had elapsed.
+    ///
+    /// See [`ShutdownReport::aborted_tasks`] for more information.
+    pub fn aborted_tasks(&self) -> usize {
+        self.aborted_tasks
+    }
+}
+
+/// Describes how the waiting for [`ShutdownGuard`]s to be dropped ended.
+///
+/// [`ShutdownGuard`]: crate::ShutdownGuard
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum DrainOutcome {
+    /// All guards were dropped.
+    Completed,
+    /// The time limit was reached while guards were still alive.
+    TimedOut,
+    /// The overwrite signal was triggered while guards were still alive.
+    Overwritten,
+}
+
+/// The reason why a [`Shutdown`] was triggered.
+///
+/// [`Shutdown`]: crate::Shutdown
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum ShutdownReason {
+    /// The (custom) shutdown signal of the [`Shutdown`] was triggered.
+    ///
+    /// [`Shutdown`]: crate::Shutdown
+    Signal,
+    /// An operating system signal was received, either one of the default signals
+    /// or one of those configured using [`ShutdownBuilder::with_unix_signals`],
+    /// identified by the contained [`OsSignal`].
+    ///
+    /// [`ShutdownBuilder::with_unix_signals`]: crate::ShutdownBuilder::with_unix_signals
+    OsSignal(OsSignal),
+    /// The shutdown was triggered manually using a [`ShutdownTrigger`].
+    ///
+    /// [`ShutdownTrigger`]: crate::ShutdownTrigger
+    Manual,
+    /// The parent of a child [`Shutdown`] was cancelled.
+    ///
+    /// [`Shutdown`]: crate::Shutdown
+    ParentCancelled,
+    /// A critical task exited.
+    CriticalTask(CriticalTaskExit),
+}
+
+impl ShutdownReason {
+    /// Returns the [`OsSignal`] which triggered the shutdown,
+    /// or `None` if the shutdown was not triggered by an operating system signal.
+    pub fn os_signal(&self) -> Option<OsSignal> {
+        match self {
+            ShutdownReason::OsSignal(signal) => Some(*signal),
+            _ => None,
+        }
+    }
+}
+
+impl fmt::Display for ShutdownReason {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ShutdownReason::Signal => write!(f, "shutdown signal triggered"),
+            ShutdownReason::OsSignal(signal) => write!(f, "{} received", signal),
+            ShutdownReason::Manual => write!(f, "manual trigger"),
+            ShutdownReason::ParentCancelled => write!(f, "parent cancelled"),
+            ShutdownReason::CriticalTask(exit) => write!(f, "critical task {}", exit),
+        }
+    }
+}
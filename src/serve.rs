@@ -0,0 +1,183 @@
+//! Integration with [`hyper`] and [`hyper_util`],
+//! to serve a connection until the [`Shutdown`] is cancelled,
+//! after which it is shutdown gracefully.
+//!
+//! [`Shutdown`]: crate::Shutdown
+
+use std::{
+    error::Error,
+    future::Future,
+    pin::Pin,
+    task::{Context, Poll},
+};
+
+use hyper::{
+    body::{Body, Incoming},
+    header::{HeaderValue, CONNECTION},
+    rt::{Read, Write},
+    service::Service,
+    Request, Response, Version,
+};
+use hyper_util::{
+    rt::TokioExecutor,
+    server::conn::auto::{Builder, HttpServerConnExec},
+};
+use pin_project_lite::pin_project;
+
+use crate::{trigger::Receiver, ShutdownGuard};
+
+/// Serves a connection using the [`hyper_util`] auto [`Builder`] (HTTP/1 and HTTP/2),
+/// until the connection is closed or the [`Shutdown`] of the given guard is cancelled,
+/// after which the connection is shutdown gracefully and polled to completion.
+///
+/// HTTP/1 responses get a `Connection: close` header once the shutdown signal
+/// has been triggered, such that clients stop reusing the connection
+/// while the delay (if any) is being awaited.
+///
+/// Use [`serve_connection_with`] to serve the connection using a configured [`Builder`].
+///
+/// ```no_run
+/// use std::convert::Infallible;
+///
+/// use hyper::{body::Incoming, service::service_fn, Request, Response};
+/// use hyper_util::rt::TokioIo;
+/// use tokio_graceful::Shutdown;
+///
+/// async fn hello(_: Request<Incoming>) -> Result<Response<String>, Infallible> {
+///     Ok(Response::new("Hello World!".to_owned()))
+/// }
+///
+/// # async fn example() {
+/// let shutdown = Shutdown::default();
+/// let listener = tokio::net::TcpListener::bind("127.0.0.1:8080").await.unwrap();
+/// shutdown.spawn_task_fn(move |guard| async move {
+///     while let Some(Ok((stream, _))) = guard.run_until_cancelled(listener.accept()).await {
+///         guard.spawn_task_fn(move |guard| async move {
+///             let io = TokioIo::new(stream);
+///             let _ = tokio_graceful::serve_connection(io, service_fn(hello), guard).await;
+///         });
+///     }
+/// });
+/// shutdown.shutdown().await;
+/// # }
+/// ```
+///
+/// [`Shutdown`]: crate::Shutdown
+pub async fn serve_connection<I, S, B>(
+    io: I,
+    service: S,
+    guard: ShutdownGuard,
+) -> Result<(), Box<dyn Error + Send + Sync>>
+where
+    I: Read + Write + Unpin + 'static,
+    S: Service<Request<Incoming>, Response = Response<B>>,
+    S::Future: 'static,
+    S::Error: Into<Box<dyn Error + Send + Sync>>,
+    B: Body + 'static,
+    B::Error: Into<Box<dyn Error + Send + Sync>>,
+    TokioExecutor: HttpServerConnExec<ConnectionCloseFuture<S::Future>, B>,
+{
+    serve_connection_with(&Builder::new(TokioExecutor::new()), io, service, guard).await
+}
+
+/// Serves a connection using the given [`hyper_util`] auto [`Builder`],
+/// until the connection is closed or the [`Shutdown`] of the given guard is cancelled,
+/// after which the connection is shutdown gracefully and polled to completion.
+///
+/// See [`serve_connection`] for more information.
+///
+/// [`Shutdown`]: crate::Shutdown
+pub async fn serve_connection_with<I, S, B, E>(
+    builder: &Builder<E>,
+    io: I,
+    service: S,
+    guard: ShutdownGuard,
+) -> Result<(), Box<dyn Error + Send + Sync>>
+where
+    I: Read + Write + Unpin + 'static,
+    S: Service<Request<Incoming>, Response = Response<B>>,
+    S::Future: 'static,
+    S::Error: Into<Box<dyn Error + Send + Sync>>,
+    B: Body + 'static,
+    B::Error: Into<Box<dyn Error + Send + Sync>>,
+    E: HttpServerConnExec<ConnectionCloseFuture<S::Future>, B>,
+{
+    let service = ConnectionCloseService {
+        service,
+        shutdown_signal: guard.weak().shutdown_signal_rx(),
+    };
+    let conn = builder.serve_connection(io, service);
+    let mut conn = std::pin::pin!(conn);
+
+    if let Some(result) = guard.run_until_cancelled(conn.as_mut()).await {
+        return result;
+    }
+    tracing::trace!("::serve: cancelled: shutdown connection gracefully");
+    conn.as_mut().graceful_shutdown();
+    conn.await
+}
+
+/// A [`Service`] adding a `Connection: close` header to HTTP/1 responses
+/// once the shutdown signal has been triggered.
+#[derive(Debug, Clone)]
+struct ConnectionCloseService<S> {
+    service: S,
+    shutdown_signal: Receiver,
+}
+
+impl<S, B> Service<Request<Incoming>> for ConnectionCloseService<S>
+where
+    S: Service<Request<Incoming>, Response = Response<B>>,
+{
+    type Response = S::Response;
+    type Error = S::Error;
+    type Future = ConnectionCloseFuture<S::Future>;
+
+    fn call(&self, req: Request<Incoming>) -> Self::Future {
+        let shutdown_signal = match req.version() {
+            Version::HTTP_09 | Version::HTTP_10 | Version::HTTP_11 => {
+                Some(self.shutdown_signal.clone())
+            }
+            _ => None,
+        };
+        ConnectionCloseFuture {
+            future: self.service.call(req),
+            shutdown_signal,
+        }
+    }
+}
+
+pin_project! {
+    /// The response [`Future`] of a connection served using [`serve_connection`]
+    /// or [`serve_connection_with`].
+    ///
+    /// Resolves to the response of the served [`Service`], with a `Connection: close`
+    /// header added to HTTP/1 responses once the shutdown signal has been triggered.
+    #[derive(Debug)]
+    #[must_use = "futures do nothing unless you `.await` or poll them"]
+    pub struct ConnectionCloseFuture<F> {
+        #[pin]
+        future: F,
+        shutdown_signal: Option<Receiver>,
+    }
+}
+
+impl<F, B, E> Future for ConnectionCloseFuture<F>
+where
+    F: Future<Output = Result<Response<B>, E>>,
+{
+    type Output = F::Output;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        let this = self.project();
+        let mut result = std::task::ready!(this.future.poll(cx));
+        if let (Ok(response), Some(shutdown_signal)) = (&mut result, this.shutdown_signal) {
+            if shutdown_signal.is_triggered() {
+                response
+                    .headers_mut()
+                    .insert(CONNECTION, HeaderValue::from_static("close"));
+            }
+        }
+        Poll::Ready(result)
+    }
+}
@@ -1,9 +1,21 @@
+#[cfg(feature = "leak-detection")]
+use crate::leak::LeakDetector;
 use crate::{
-    sync::JoinHandle,
-    trigger::{trigger, Receiver},
-    ShutdownGuard, WeakShutdownGuard,
+    lifecycle::LifecycleSender,
+    readiness::ReadinessState,
+    registry::Registry,
+    reload::{ReloadGuard, ReloadTrigger},
+    report::DrainStats,
+    sync::{Arc, JoinHandle, Mutex, Ordering},
+    task::TaskTracker,
+    timeline::{TimelineEventKind, TimelineRecorder},
+    trigger::{trigger, Receiver, Sender},
+    DrainOutcome, GuardInfo, Lifecycle, PhaseReport, Readiness, ShutdownGuard, ShutdownReason,
+    ShutdownReport, WeakShutdownGuard,
 };
 use std::{
+    borrow::Cow,
+    convert::Infallible,
     fmt,
     future::Future,
     time::{self, Duration},
@@ -12,6 +24,7 @@ use std::{
 /// [`ShutdownBuilder`] to build a [`Shutdown`] manager.
 pub struct ShutdownBuilder<T> {
     data: T,
+    options: ShutdownOptions,
 }
 
 impl Default for ShutdownBuilder<sealed::WithSignal<sealed::Default>> {
@@ -24,6 +37,7 @@ impl<T: fmt::Debug> fmt::Debug for ShutdownBuilder<T> {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         f.debug_struct("ShutdownBuilder")
             .field("data", &self.data)
+            .field("options", &self.options)
             .finish()
     }
 }
@@ -34,9 +48,10 @@ impl ShutdownBuilder<sealed::WithSignal<sealed::Default>> {
     pub fn new() -> Self {
         Self {
             data: sealed::WithSignal {
-                signal: sealed::Default,
+                signal: sealed::Default::default(),
                 delay: None,
             },
+            options: ShutdownOptions::default(),
         }
     }
 
@@ -45,9 +60,91 @@ impl ShutdownBuilder<sealed::WithSignal<sealed::Default>> {
     pub fn without_signal(self) -> ShutdownBuilder<sealed::WithoutSignal> {
         ShutdownBuilder {
             data: sealed::WithoutSignal,
+            options: self.options,
         }
     }
 
+    /// Create a [`ShutdownBuilder`] which forces the shutdown once the
+    /// default signal is received again (or as many times as configured),
+    /// skipping the delay and the waiting for all jobs to be complete.
+    ///
+    /// This is a built-in alternative to [`ShutdownBuilder::with_overwrite_fn`],
+    /// where the overwrite signal is the default signal. See [`Escalation`]
+    /// for more information on how to configure the escalation.
+    ///
+    /// ```no_run
+    /// use tokio_graceful::{Escalation, Shutdown};
+    ///
+    /// # async fn example() {
+    /// let shutdown = Shutdown::builder()
+    ///     .with_escalation(Escalation::new(2).with_message("press Ctrl-C again to force"))
+    ///     .build();
+    /// # }
+    /// ```
+    ///
+    /// [`Escalation`]: crate::Escalation
+    #[cfg(all(not(loom), any(unix, windows)))]
+    pub fn with_escalation(
+        self,
+        escalation: crate::Escalation,
+    ) -> ShutdownBuilder<sealed::WithSignalAndOverwriteFn<sealed::Default, crate::Escalation>> {
+        ShutdownBuilder {
+            data: sealed::WithSignalAndOverwriteFn {
+                overwrite_fn: escalation.with_signals(
+                    self.data.signal.signals.clone(),
+                    self.data.signal.listener.clone(),
+                ),
+                signal: self.data.signal,
+                delay: self.data.delay,
+            },
+            options: self.options,
+        }
+    }
+
+    /// Create a [`ShutdownBuilder`] which is triggered by the given Unix signals,
+    /// instead of the default signals (see [`default_signal`]).
+    ///
+    /// The signal which triggered the shutdown is recorded as the
+    /// [`ShutdownReason`], available using [`Shutdown::shutdown_reason`],
+    /// the guards and the [`ShutdownReport`].
+    ///
+    /// In case an [`Escalation`] is used, configure the signals prior to it,
+    /// such that the escalation listens to the same signals.
+    ///
+    /// # Panics
+    ///
+    /// This method panics in case no signals are given,
+    /// as the shutdown would never be triggered by a signal.
+    ///
+    /// ```no_run
+    /// use tokio::signal::unix::SignalKind;
+    /// use tokio_graceful::Shutdown;
+    ///
+    /// # async fn example() {
+    /// let shutdown = Shutdown::builder()
+    ///     .with_unix_signals([
+    ///         SignalKind::terminate(),
+    ///         SignalKind::quit(),
+    ///         SignalKind::hangup(),
+    ///     ])
+    ///     .build();
+    /// # }
+    /// ```
+    ///
+    /// [`default_signal`]: crate::default_signal
+    /// [`Escalation`]: crate::Escalation
+    /// [`ShutdownReport`]: crate::ShutdownReport
+    #[cfg(all(not(loom), unix))]
+    pub fn with_unix_signals(
+        mut self,
+        signals: impl IntoIterator<Item = tokio::signal::unix::SignalKind>,
+    ) -> Self {
+        let signals: Vec<_> = signals.into_iter().collect();
+        assert!(!signals.is_empty(), "at least one unix signal is required");
+        self.data.signal.signals = crate::signal::SignalSet::Unix(signals);
+        self
+    }
+
     /// Create a [`ShutdownBuilder`] with a custom [`Future`] signal.
     pub fn with_signal<F: Future + Send + 'static>(
         self,
@@ -58,6 +155,7 @@ impl ShutdownBuilder<sealed::WithSignal<sealed::Default>> {
                 signal: future,
                 delay: self.data.delay,
             },
+            options: self.options,
         }
     }
 }
@@ -80,6 +178,7 @@ impl<S> ShutdownBuilder<sealed::WithSignal<S>> {
                 overwrite_fn: f,
                 delay: self.data.delay,
             },
+            options: self.options,
         }
     }
 
@@ -92,6 +191,7 @@ impl<S> ShutdownBuilder<sealed::WithSignal<S>> {
                 signal: self.data.signal,
                 delay: Some(delay),
             },
+            options: self.options,
         }
     }
 
@@ -104,6 +204,7 @@ impl<S> ShutdownBuilder<sealed::WithSignal<S>> {
                 signal: self.data.signal,
                 delay,
             },
+            options: self.options,
         }
     }
 
@@ -127,6 +228,7 @@ impl<S, F> ShutdownBuilder<sealed::WithSignalAndOverwriteFn<S, F>> {
                 overwrite_fn: self.data.overwrite_fn,
                 delay: Some(delay),
             },
+            options: self.options,
         }
     }
 
@@ -140,6 +242,7 @@ impl<S, F> ShutdownBuilder<sealed::WithSignalAndOverwriteFn<S, F>> {
                 overwrite_fn: self.data.overwrite_fn,
                 delay,
             },
+            options: self.options,
         }
     }
 
@@ -152,18 +255,223 @@ impl<S, F> ShutdownBuilder<sealed::WithSignalAndOverwriteFn<S, F>> {
     }
 }
 
+impl<T> ShutdownBuilder<T> {
+    /// Add a named phase to this [`ShutdownBuilder`],
+    /// which will be run once all [`ShutdownGuard`]s of the [`Shutdown`]
+    /// have been dropped (or the time limit has been reached).
+    ///
+    /// Phases are run in the order they were added, and each phase
+    /// has its own guards, created using [`Shutdown::phase_guard`].
+    /// These guards are only cancelled once their phase starts.
+    /// The next phase starts once all guards of the current phase
+    /// have been dropped or its time budget has elapsed.
+    ///
+    /// # Panics
+    ///
+    /// This method panics in case a phase with the same name was already added,
+    /// or in case the name is "main", which is reserved for the [`Shutdown`] itself.
+    pub fn with_phase(mut self, name: impl Into<Cow<'static, str>>, budget: Duration) -> Self {
+        let name = name.into();
+        assert!(
+            name != "main",
+            "the phase name \"main\" is reserved for the shutdown itself"
+        );
+        assert!(
+            self.options.phases.iter().all(|phase| phase.name != name),
+            "a phase named \"{}\" was already added",
+            name
+        );
+        self.options.phases.push(PhaseOptions { name, budget });
+        self
+    }
+
+    /// Track all tasks spawned using the guards of the [`Shutdown`],
+    /// such that the tasks which are still running once the time limit
+    /// of [`Shutdown::shutdown_with_limit`] is reached are aborted.
+    ///
+    /// The same applies to the tasks spawned using the guards of a phase,
+    /// which are aborted once the time budget of their phase has elapsed.
+    ///
+    /// Tasks spawned once the time limit (or budget) was reached are aborted right away.
+    ///
+    /// The shutdown only continues once the aborted tasks are confirmed
+    /// to be dropped, the amount of which is available in the [`ShutdownReport`]
+    /// and [`PhaseReport`]. Do note that a task which blocks (e.g. without yielding)
+    /// cannot be aborted, and will therefore delay the shutdown until it yields.
+    ///
+    /// Tracking tasks is not lock-free, and thus opt-in.
+    ///
+    /// [`ShutdownReport`]: crate::ShutdownReport
+    /// [`PhaseReport`]: crate::PhaseReport
+    pub fn with_abort_on_timeout(mut self) -> Self {
+        self.options.abort_on_timeout = true;
+        self
+    }
+
+    /// Record the [`Timeline`] of the shutdown: the signal, the start and end
+    /// of the delay, the cancellation of the guards, each guard released
+    /// (with its name, if named) since, the phases and the final outcome.
+    ///
+    /// The timeline is available using [`ShutdownReport::timeline`],
+    /// and can be exported as JSON or in the Chrome trace event format.
+    ///
+    /// Recording the released guards is not lock-free, and thus opt-in.
+    ///
+    /// [`Timeline`]: crate::Timeline
+    /// [`ShutdownReport::timeline`]: crate::ShutdownReport::timeline
+    pub fn with_timeline(mut self) -> Self {
+        self.options.timeline = true;
+        self
+    }
+
+    /// Detect leaked guards, e.g. guards which were `mem::forget`-ed
+    /// or are stuck in a detached task, and thus prevent the shutdown from completing.
+    ///
+    /// A backtrace is captured each time a [`ShutdownGuard`] is created, cloned
+    /// or upgraded from a [`WeakShutdownGuard`]. Once the waiting for the guards
+    /// to be dropped took longer than the given threshold, the backtraces
+    /// of all guards which are still alive are logged as warnings.
+    /// Each phase is checked separately, only reporting its own guards.
+    ///
+    /// Capturing backtraces is expensive and not lock-free,
+    /// and thus meant for debugging purposes only.
+    #[cfg(feature = "leak-detection")]
+    pub fn with_leak_detection(mut self, threshold: Duration) -> Self {
+        self.options.leak_detection = Some(threshold);
+        self
+    }
+
+    /// Report the progress of the drain stage every given interval, while
+    /// waiting for all guards to be dropped, such that a long drain
+    /// can be told apart from a hanging one.
+    ///
+    /// Each report is logged (at the info level) with the amount of guards remaining,
+    /// the time spent draining so far and the names of the guards which are still alive
+    /// (see [`ShutdownGuard::named`]). The phases of the [`Shutdown`]
+    /// report their progress as well, each only concerning its own guards.
+    ///
+    /// [`ShutdownGuard::named`]: crate::ShutdownGuard::named
+    pub fn with_drain_progress(mut self, interval: Duration) -> Self {
+        self.options.drain_progress = Some(interval);
+        self
+    }
+
+    /// Request a reload each time the given Unix signal is received,
+    /// until the [`Shutdown`] is cancelled. Can be called multiple times
+    /// to request a reload for multiple signals.
+    ///
+    /// Reloads can be awaited on using a [`ReloadGuard`],
+    /// see [`Shutdown::reload_guard`] for more information.
+    ///
+    /// Do note that once a signal is registered, it is no longer handled
+    /// by its default handler (e.g. terminating the process on SIGHUP),
+    /// not even once the [`Shutdown`] is cancelled.
+    /// This option has no effect for a [`Shutdown`] without signal.
+    ///
+    /// ```no_run
+    /// use tokio::signal::unix::SignalKind;
+    /// use tokio_graceful::Shutdown;
+    ///
+    /// # async fn example() {
+    /// let shutdown = Shutdown::builder()
+    ///     .with_reload_signal(SignalKind::hangup())
+    ///     .build();
+    /// # }
+    /// ```
+    ///
+    /// [`ReloadGuard`]: crate::ReloadGuard
+    #[cfg(all(not(loom), unix))]
+    pub fn with_reload_signal(mut self, signal: tokio::signal::unix::SignalKind) -> Self {
+        self.options.reload_signals.push(signal);
+        self
+    }
+
+    /// Notify the systemd service manager (using `$NOTIFY_SOCKET`, see `sd_notify(3)`)
+    /// of the lifecycle of the [`Shutdown`]:
+    ///
+    /// - `READY=1` once its [`Readiness`] is set to ready (see [`Readiness::set_ready`]);
+    /// - `STOPPING=1` once the shutdown signal is triggered;
+    /// - `EXTEND_TIMEOUT_USEC` at the start of each stage of the shutdown which has a time limit,
+    ///   such that systemd does not kill the service while it is still draining:
+    ///   once the waiting for the guards starts within [`Shutdown::shutdown_with_limit`]
+    ///   (extending it by the limit), once each phase starts (extending it by its budget)
+    ///   and, in case tasks are aborted (see [`ShutdownBuilder::with_abort_on_timeout`]),
+    ///   once the waiting for the aborted tasks to be dropped starts (extending it by 5 seconds).
+    ///
+    /// Nothing is sent when the service is not running under systemd.
+    ///
+    /// [`Readiness`]: crate::Readiness
+    /// [`Readiness::set_ready`]: crate::Readiness::set_ready
+    #[cfg(all(feature = "systemd", unix))]
+    pub fn with_systemd_notify(mut self) -> Self {
+        self.options.systemd_notify = true;
+        self
+    }
+
+    /// Build the [`Shutdown`] as a child of the [`Shutdown`] linked to the given guard.
+    ///
+    /// The child is cancelled when either its own signal is triggered
+    /// or its parent is cancelled, and shares the reloads of its parent.
+    /// As long as the child has [`ShutdownGuard`]s alive,
+    /// the parent is prevented from shutting down.
+    ///
+    /// In contrast to [`ShutdownGuard::child`] no other options are inherited
+    /// from the parent, allowing the child to be configured as desired.
+    ///
+    /// ```
+    /// use std::time::Duration;
+    /// use tokio_graceful::Shutdown;
+    ///
+    /// # async fn example() {
+    /// let shutdown = Shutdown::default();
+    /// let child = Shutdown::builder()
+    ///     .with_signal(std::future::pending::<()>())
+    ///     .with_delay(Duration::from_millis(100))
+    ///     .with_phase("flush", Duration::from_secs(1))
+    ///     .with_parent(&shutdown.guard())
+    ///     .build();
+    /// # }
+    /// ```
+    ///
+    /// [`ShutdownGuard::child`]: crate::ShutdownGuard::child
+    pub fn with_parent(mut self, parent: &ShutdownGuard) -> Self {
+        self.options.reload_trigger = Some(parent.reload_trigger());
+        self.options.scope = Some(parent.registry().child_scope(parent.name().as_deref()));
+        self.options.parent = Some(parent.clone_weak());
+        self
+    }
+}
+
 impl ShutdownBuilder<sealed::WithoutSignal> {
     /// Build a [`Shutdown`] that acts like a WaitGroup.
-    pub fn build(self) -> Shutdown {
+    pub fn build(mut self) -> Shutdown {
+        let parent = self.options.parent.take();
         let (zero_tx, zero_rx) = trigger();
+        let (manual_tx, _) = trigger();
+        let shutdown_trigger = ShutdownTrigger::new(manual_tx, &self.options);
+        let registry = self.options.registry();
+        let task_tracker = self.options.task_tracker();
+        let reload_trigger = self.options.reload_trigger.clone().unwrap_or_default();
 
-        let guard = ShutdownGuard::new(Receiver::closed(), None, zero_tx, Default::default());
+        let guard = ShutdownGuard::new(
+            Receiver::closed(),
+            None,
+            zero_tx,
+            Default::default(),
+            shutdown_trigger.clone(),
+            registry.clone(),
+            task_tracker,
+            reload_trigger.clone(),
+        );
 
         Shutdown {
             guard,
             zero_rx,
             zero_overwrite_rx: Receiver::pending(),
+            drain_progress: self.options.drain_progress,
+            phases: Phase::build_all(self.options, &shutdown_trigger, &reload_trigger),
         }
+        .linked_to(parent)
     }
 }
 
@@ -171,7 +479,8 @@ impl<I: sealed::IntoFuture> ShutdownBuilder<sealed::WithSignal<I>> {
     /// Build a [`Shutdown`] which will allow a shutdown
     /// when the shutdown signal has been triggered AND
     /// all jobs are complete.
-    pub fn build(self) -> Shutdown {
+    pub fn build(mut self) -> Shutdown {
+        let parent = self.options.parent.take();
         let trigger_signal = self.data.signal.into_future();
 
         let (delay_tuple, maybe_shutdown_signal_rx) = match self.data.delay {
@@ -182,6 +491,13 @@ impl<I: sealed::IntoFuture> ShutdownBuilder<sealed::WithSignal<I>> {
             None => (None, None),
         };
 
+        let (manual_tx, manual_rx) = trigger();
+        let shutdown_trigger = ShutdownTrigger::new(manual_tx, &self.options);
+        let signal_shutdown_trigger = shutdown_trigger.clone();
+        let registry = self.options.registry();
+        let task_tracker = self.options.task_tracker();
+        let reload_trigger = self.options.reload_trigger.clone().unwrap_or_default();
+
         let (signal_tx, signal_rx) = trigger();
         let (zero_tx, zero_rx) = trigger();
 
@@ -190,10 +506,30 @@ impl<I: sealed::IntoFuture> ShutdownBuilder<sealed::WithSignal<I>> {
             maybe_shutdown_signal_rx,
             zero_tx,
             Default::default(),
+            shutdown_trigger.clone(),
+            registry.clone(),
+            task_tracker,
+            reload_trigger.clone(),
         );
 
+        #[cfg(all(not(loom), unix))]
+        crate::reload::spawn_reload_signals(&self.options.reload_signals, guard.clone_weak());
+
         crate::sync::spawn(async move {
-            let _ = trigger_signal.await;
+            tokio::select! {
+                reason = trigger_signal => {
+                    signal_shutdown_trigger.record_reason(reason);
+                }
+                _ = manual_rx => {
+                    tracing::trace!("::trigger signal recieved: manual trigger");
+                }
+            }
+            signal_shutdown_trigger.record_signal_received(
+                delay_tuple
+                    .as_ref()
+                    .map(|(delay, _)| *delay)
+                    .unwrap_or_default(),
+            );
             if let Some((delay, shutdown_signal_tx)) = delay_tuple {
                 shutdown_signal_tx.trigger();
                 tracing::trace!(
@@ -202,6 +538,7 @@ impl<I: sealed::IntoFuture> ShutdownBuilder<sealed::WithSignal<I>> {
                 );
                 tokio::time::sleep(delay).await;
             }
+            signal_shutdown_trigger.record_cancelled();
             signal_tx.trigger();
         });
 
@@ -209,21 +546,24 @@ impl<I: sealed::IntoFuture> ShutdownBuilder<sealed::WithSignal<I>> {
             guard,
             zero_rx,
             zero_overwrite_rx: Receiver::pending(),
+            drain_progress: self.options.drain_progress,
+            phases: Phase::build_all(self.options, &shutdown_trigger, &reload_trigger),
         }
+        .linked_to(parent)
     }
 }
 
-impl<I, F, Fut> ShutdownBuilder<sealed::WithSignalAndOverwriteFn<I, F>>
+impl<I, F> ShutdownBuilder<sealed::WithSignalAndOverwriteFn<I, F>>
 where
     I: sealed::IntoFuture,
-    F: FnOnce() -> Fut + Send + 'static,
-    Fut: Future + Send + 'static,
+    F: sealed::IntoOverwriteFuture,
 {
     /// Build a [`Shutdown`] which will allow a shutdown
     /// when the shutdown signal has been triggered AND
     /// either all jobs are complete or the overwrite (force)
     /// signal has been triggered instead.
-    pub fn build(self) -> Shutdown {
+    pub fn build(mut self) -> Shutdown {
+        let parent = self.options.parent.take();
         let trigger_signal = self.data.signal.into_future();
         let overwrite_fn = self.data.overwrite_fn;
 
@@ -235,6 +575,13 @@ where
             None => (None, None),
         };
 
+        let (manual_tx, manual_rx) = trigger();
+        let shutdown_trigger = ShutdownTrigger::new(manual_tx, &self.options);
+        let signal_shutdown_trigger = shutdown_trigger.clone();
+        let registry = self.options.registry();
+        let task_tracker = self.options.task_tracker();
+        let reload_trigger = self.options.reload_trigger.clone().unwrap_or_default();
+
         let (signal_tx, signal_rx) = trigger();
         let (zero_tx, zero_rx) = trigger();
         let (zero_overwrite_tx, zero_overwrite_rx) = trigger();
@@ -244,15 +591,35 @@ where
             maybe_shutdown_signal_rx,
             zero_tx,
             Default::default(),
+            shutdown_trigger.clone(),
+            registry.clone(),
+            task_tracker,
+            reload_trigger.clone(),
         );
 
+        #[cfg(all(not(loom), unix))]
+        crate::reload::spawn_reload_signals(&self.options.reload_signals, guard.clone_weak());
+
         crate::sync::spawn(async move {
-            let _ = trigger_signal.await;
-            let overwrite_signal = overwrite_fn();
+            tokio::select! {
+                reason = trigger_signal => {
+                    signal_shutdown_trigger.record_reason(reason);
+                }
+                _ = manual_rx => {
+                    tracing::trace!("::trigger signal recieved: manual trigger");
+                }
+            }
+            let overwrite_signal = overwrite_fn.into_overwrite_future();
             crate::sync::spawn(async move {
                 let _ = overwrite_signal.await;
                 zero_overwrite_tx.trigger();
             });
+            signal_shutdown_trigger.record_signal_received(
+                delay_tuple
+                    .as_ref()
+                    .map(|(delay, _)| *delay)
+                    .unwrap_or_default(),
+            );
             if let Some((delay, shutdown_signal_tx)) = delay_tuple {
                 shutdown_signal_tx.trigger();
                 tracing::trace!(
@@ -261,6 +628,7 @@ where
                 );
                 tokio::time::sleep(delay).await;
             }
+            signal_shutdown_trigger.record_cancelled();
             signal_tx.trigger();
         });
 
@@ -268,7 +636,10 @@ where
             guard,
             zero_rx,
             zero_overwrite_rx,
+            drain_progress: self.options.drain_progress,
+            phases: Phase::build_all(self.options, &shutdown_trigger, &reload_trigger),
         }
+        .linked_to(parent)
     }
 }
 
@@ -284,11 +655,14 @@ where
 /// See the [README] for more info on how to use this crate.
 ///
 /// [`Future`]: std::future::Future
+/// [`default_signal`]: crate::default_signal
 /// [README]: https://github.com/plabayo/tokio-graceful/blob/main/README.md
 pub struct Shutdown {
     guard: ShutdownGuard,
     zero_rx: Receiver,
     zero_overwrite_rx: Receiver,
+    drain_progress: Option<Duration>,
+    phases: Vec<Phase>,
 }
 
 impl Shutdown {
@@ -317,6 +691,52 @@ impl Shutdown {
         ShutdownBuilder::default().without_signal().build()
     }
 
+    /// Creates a child [`Shutdown`], linked to the [`Shutdown`] of the given parent guard,
+    /// inheriting the options of the parent as documented by [`ShutdownGuard::child`].
+    pub(crate) fn new_child(parent: &ShutdownGuard, signal: impl Future + Send + 'static) -> Self {
+        let mut builder = ShutdownBuilder::default()
+            .with_signal(signal)
+            .with_parent(parent);
+        builder.options.inherit(parent.weak());
+        builder.build()
+    }
+
+    /// Links this [`Shutdown`] to the given parent (if any).
+    ///
+    /// The child is cancelled once its parent is cancelled. A clone of the parent guard
+    /// is held until all guards of the child are dropped, preventing the parent
+    /// from shutting down while the child is still busy.
+    fn linked_to(self, parent: Option<WeakShutdownGuard>) -> Self {
+        let Some(parent) = parent else {
+            return self;
+        };
+
+        let shutdown_trigger = self.shutdown_trigger();
+        let parent_cancelled = parent.cancelled_owned();
+        let child_cancelled = self.guard.cancelled_owned();
+        crate::sync::spawn(async move {
+            tokio::select! {
+                _ = parent_cancelled => {
+                    tracing::trace!("::child: parent cancelled");
+                    shutdown_trigger.trigger_with_reason(ShutdownReason::ParentCancelled);
+                }
+                _ = child_cancelled => {
+                    tracing::trace!("::child: cancelled");
+                }
+            }
+        });
+
+        let zero_rx = self.zero_rx.clone();
+        parent
+            .upgrade_internal()
+            .into_spawn_internal_task(async move {
+                zero_rx.await;
+                tracing::trace!("::child: all guards dropped: release parent guard");
+            });
+
+        self
+    }
+
     /// Returns a [`ShutdownGuard`] which primary use
     /// is to prevent the [`Shutdown`] from shutting down.
     ///
@@ -325,7 +745,7 @@ impl Shutdown {
     /// [`ShutdownGuard`]: crate::ShutdownGuard
     #[inline]
     pub fn guard(&self) -> ShutdownGuard {
-        self.guard.clone()
+        self.guard.create()
     }
 
     /// Returns a [`WeakShutdownGuard`] which in contrast to
@@ -347,6 +767,67 @@ impl Shutdown {
         self.guard.clone_weak()
     }
 
+    /// Returns a [`ShutdownTrigger`] which can be used to manually
+    /// trigger the shutdown of this [`Shutdown`], as an alternative
+    /// to (and in the same way as) its shutdown signal.
+    #[inline]
+    pub fn shutdown_trigger(&self) -> ShutdownTrigger {
+        self.guard.shutdown_trigger()
+    }
+
+    /// Returns the reason why the shutdown was triggered,
+    /// or `None` if the shutdown has not been triggered (yet).
+    ///
+    /// # Panics
+    ///
+    /// This method can panic if the internal mutex is poisoned.
+    #[inline]
+    pub fn shutdown_reason(&self) -> Option<ShutdownReason> {
+        self.guard.shutdown_reason()
+    }
+
+    /// Returns a [`ReloadTrigger`] which can be used to request a reload
+    /// from all [`ReloadGuard`]s linked to this [`Shutdown`].
+    ///
+    /// Child [`Shutdown`]s share the reloads of their parent.
+    ///
+    /// [`ReloadTrigger`]: crate::ReloadTrigger
+    /// [`ReloadGuard`]: crate::ReloadGuard
+    #[inline]
+    pub fn reload_trigger(&self) -> ReloadTrigger {
+        self.guard.reload_trigger()
+    }
+
+    /// Returns a [`ReloadGuard`] which can be used to wait for reloads
+    /// to be requested, until this [`Shutdown`] is cancelled.
+    ///
+    /// Reloads can be requested using a [`ReloadTrigger`],
+    /// or by the signals configured using [`ShutdownBuilder::with_reload_signal`].
+    ///
+    /// [`ReloadTrigger`]: crate::ReloadTrigger
+    /// [`ReloadGuard`]: crate::ReloadGuard
+    #[inline]
+    pub fn reload_guard(&self) -> ReloadGuard {
+        self.guard.reload_guard()
+    }
+
+    /// Returns a [`ShutdownGuard`] for the phase with the given name,
+    /// or `None` if no such phase was added to the [`ShutdownBuilder`].
+    ///
+    /// The guard is only cancelled once its phase starts,
+    /// and prevents the next phase from starting until it is dropped
+    /// or the time budget of its phase has elapsed.
+    ///
+    /// See [`ShutdownBuilder::with_phase`] for more information.
+    ///
+    /// [`ShutdownGuard`]: crate::ShutdownGuard
+    pub fn phase_guard(&self, name: &str) -> Option<ShutdownGuard> {
+        self.phases
+            .iter()
+            .find(|phase| phase.name == name)
+            .map(|phase| phase.guard.create())
+    }
+
     /// Returns a Tokio [`crate::sync::JoinHandle`] that can be awaited on
     /// to wait for the spawned task to complete. See
     /// [`crate::sync::spawn`] for more information.
@@ -372,11 +853,162 @@ impl Shutdown {
         self.guard.spawn_task_fn(task)
     }
 
+    /// Returns a Tokio [`crate::sync::JoinHandle`] that can be awaited on
+    /// to wait for the spawned task to complete.
+    ///
+    /// See [`ShutdownGuard::spawn_task_named`] for more information.
+    ///
+    /// [`ShutdownGuard::spawn_task_named`]: crate::ShutdownGuard::spawn_task_named
+    #[inline]
+    #[track_caller]
+    pub fn spawn_task_named<T>(
+        &self,
+        name: impl Into<Cow<'static, str>>,
+        task: T,
+    ) -> JoinHandle<T::Output>
+    where
+        T: Future + Send + 'static,
+        T::Output: Send + 'static,
+    {
+        self.guard.spawn_task_named(name, task)
+    }
+
+    /// Returns a Tokio [`crate::sync::JoinHandle`] that can be awaited on
+    /// to wait for the spawned task (fn) to complete.
+    ///
+    /// See [`ShutdownGuard::spawn_task_fn_named`] for more information.
+    ///
+    /// [`ShutdownGuard::spawn_task_fn_named`]: crate::ShutdownGuard::spawn_task_fn_named
+    #[inline]
+    #[track_caller]
+    pub fn spawn_task_fn_named<T, F>(
+        &self,
+        name: impl Into<Cow<'static, str>>,
+        task: F,
+    ) -> JoinHandle<T::Output>
+    where
+        T: Future + Send + 'static,
+        T::Output: Send + 'static,
+        F: FnOnce(ShutdownGuard) -> T + Send + 'static,
+    {
+        self.guard.spawn_task_fn_named(name, task)
+    }
+
+    /// Returns a Tokio [`crate::sync::JoinHandle`] that can be awaited on
+    /// to wait for the spawned critical task to complete.
+    ///
+    /// See [`ShutdownGuard::spawn_critical_task`] for more information.
+    ///
+    /// [`ShutdownGuard::spawn_critical_task`]: crate::ShutdownGuard::spawn_critical_task
+    #[inline]
+    pub fn spawn_critical_task<T>(&self, task: T) -> JoinHandle<T::Output>
+    where
+        T: Future + Send + 'static,
+        T::Output: Send + 'static,
+    {
+        self.guard.spawn_critical_task(task)
+    }
+
+    /// Returns a Tokio [`crate::sync::JoinHandle`] that can be awaited on
+    /// to wait for the spawned critical task (fn) to complete.
+    ///
+    /// See [`ShutdownGuard::spawn_critical_task_fn`] for more information.
+    ///
+    /// [`ShutdownGuard::spawn_critical_task_fn`]: crate::ShutdownGuard::spawn_critical_task_fn
+    #[inline]
+    pub fn spawn_critical_task_fn<T, F>(&self, task: F) -> JoinHandle<T::Output>
+    where
+        T: Future + Send + 'static,
+        T::Output: Send + 'static,
+        F: FnOnce(ShutdownGuard) -> T + Send + 'static,
+    {
+        self.guard.spawn_critical_task_fn(task)
+    }
+
+    /// Returns a Tokio [`crate::sync::JoinHandle`] that can be awaited on
+    /// to wait for the spawned fallible critical task to complete.
+    ///
+    /// See [`ShutdownGuard::spawn_fallible_critical_task`] for more information.
+    ///
+    /// [`ShutdownGuard::spawn_fallible_critical_task`]: crate::ShutdownGuard::spawn_fallible_critical_task
+    #[inline]
+    pub fn spawn_fallible_critical_task<T, O, E>(&self, task: T) -> JoinHandle<T::Output>
+    where
+        T: Future<Output = Result<O, E>> + Send + 'static,
+        O: Send + 'static,
+        E: fmt::Display + Send + 'static,
+    {
+        self.guard.spawn_fallible_critical_task(task)
+    }
+
+    /// Returns a Tokio [`crate::sync::JoinHandle`] that can be awaited on
+    /// to wait for the spawned fallible critical task (fn) to complete.
+    ///
+    /// See [`ShutdownGuard::spawn_fallible_critical_task_fn`] for more information.
+    ///
+    /// [`ShutdownGuard::spawn_fallible_critical_task_fn`]: crate::ShutdownGuard::spawn_fallible_critical_task_fn
+    #[inline]
+    pub fn spawn_fallible_critical_task_fn<T, F, O, E>(&self, task: F) -> JoinHandle<T::Output>
+    where
+        T: Future<Output = Result<O, E>> + Send + 'static,
+        F: FnOnce(ShutdownGuard) -> T + Send + 'static,
+        O: Send + 'static,
+        E: fmt::Display + Send + 'static,
+    {
+        self.guard.spawn_fallible_critical_task_fn(task)
+    }
+
+    /// Returns the [`Readiness`] of this [`Shutdown`], which can be used
+    /// to publish (e.g. to a load balancer) whether the service is ready,
+    /// draining or stopped.
+    ///
+    /// [`Readiness`]: crate::Readiness
+    #[inline]
+    pub fn readiness(&self) -> Readiness {
+        self.guard.readiness()
+    }
+
+    /// Returns the current [`Lifecycle`] state of this [`Shutdown`].
+    ///
+    /// [`Lifecycle`]: crate::Lifecycle
+    #[inline]
+    pub fn lifecycle(&self) -> Lifecycle {
+        self.guard.lifecycle()
+    }
+
+    /// Returns a [`watch::Receiver`] which can be used to (asynchronously)
+    /// watch the [`Lifecycle`] of this [`Shutdown`] for changes,
+    /// also once the [`Shutdown`] itself is consumed to wait for its guards.
+    ///
+    /// [`Lifecycle`]: crate::Lifecycle
+    /// [`watch::Receiver`]: tokio::sync::watch::Receiver
+    #[inline]
+    pub fn watch_lifecycle(&self) -> tokio::sync::watch::Receiver<Lifecycle> {
+        self.guard.watch_lifecycle()
+    }
+
+    /// Returns the info of all named [`ShutdownGuard`]s which are still alive,
+    /// oldest first, not including those of its phases.
+    ///
+    /// Unnamed guards are not listed, see [`ShutdownGuard::named`]
+    /// for more information on how to name a guard.
+    ///
+    /// # Panics
+    ///
+    /// This method can panic if the internal mutex is poisoned.
+    ///
+    /// [`ShutdownGuard`]: crate::ShutdownGuard
+    /// [`ShutdownGuard::named`]: crate::ShutdownGuard::named
+    pub fn live_guards(&self) -> Vec<GuardInfo> {
+        self.guard.registry().snapshot()
+    }
+
     /// Returns a future that completes once the [`Shutdown`] has been triggered
     /// and all [`ShutdownGuard`]s have been dropped.
     ///
-    /// The resolved [`Duration`] is the time it took for the [`Shutdown`] to
-    /// to wait for all [`ShutdownGuard`]s to be dropped.
+    /// The resolved [`ShutdownReport`] contains the reason of the shutdown,
+    /// the time each of its stages took and how the waiting for
+    /// all [`ShutdownGuard`]s to be dropped ended.
     ///
     /// You can use [`Shutdown::shutdown_with_limit`] to limit the time the
     /// [`Shutdown`] waits for all [`ShutdownGuard`]s to be dropped.
@@ -386,43 +1018,25 @@ impl Shutdown {
     /// This method can panic if the internal mutex is poisoned.
     ///
     /// [`ShutdownGuard`]: crate::ShutdownGuard
-    /// [`Duration`]: std::time::Duration
-    pub async fn shutdown(mut self) -> time::Duration {
-        tracing::info!("::shutdown: waiting for signal to trigger (read: to be cancelled)");
-        let weak_guard = self.guard.downgrade();
-        let start: time::Instant = time::Instant::now();
-        tokio::select! {
-            _ = weak_guard.cancelled() => {
-                tracing::info!("::shutdown: waiting for all guards to drop");
-            }
-            _ = &mut self.zero_overwrite_rx => {
-                let elapsed = start.elapsed();
-                tracing::warn!("::shutdown: enforced: overwrite delayed cancellation after {}s", elapsed.as_secs_f64());
-                return elapsed;
-            }
-        };
-
-        let start: time::Instant = time::Instant::now();
-        tokio::select! {
-            _ = self.zero_rx => {
-                let elapsed = start.elapsed();
-                tracing::info!("::shutdown: ready after {}s", elapsed.as_secs_f64());
-                elapsed
-            }
-            _ = self.zero_overwrite_rx => {
-                let elapsed = start.elapsed();
-                tracing::warn!("::shutdown: enforced: overwrite signal triggered after {}s", elapsed.as_secs_f64());
-                elapsed
-            }
-        }
+    /// [`ShutdownReport`]: crate::ShutdownReport
+    pub async fn shutdown(self) -> ShutdownReport {
+        self.wait_for_shutdown(None).await
     }
 
     /// Returns a future that completes once the [`Shutdown`] has been triggered
     /// and all [`ShutdownGuard`]s have been dropped or the given [`Duration`]
     /// has elapsed.
     ///
-    /// The resolved [`Duration`] is the time it took for the [`Shutdown`] to
-    /// to wait for all [`ShutdownGuard`]s to be dropped.
+    /// The resolved [`ShutdownReport`] contains the reason of the shutdown,
+    /// the time each of its stages took and how the waiting for
+    /// all [`ShutdownGuard`]s to be dropped ended. In case the limit was reached,
+    /// a phase exceeded its time budget or the overwrite signal was triggered,
+    /// the shutdown was not graceful (see [`ShutdownReport::is_graceful`])
+    /// and the report is available via the returned [`TimeoutError`] instead.
+    ///
+    /// The limit does not apply to the phases of the [`Shutdown`],
+    /// as each phase has its own time budget. These phases are still
+    /// run in case the limit was reached.
     ///
     /// You can use [`Shutdown::shutdown`] to wait for all [`ShutdownGuard`]s
     /// to be dropped without a time limit.
@@ -432,108 +1046,697 @@ impl Shutdown {
     /// This method can panic if the internal mutex is poisoned.
     ///
     /// [`ShutdownGuard`]: crate::ShutdownGuard
+    /// [`ShutdownReport`]: crate::ShutdownReport
     /// [`Duration`]: std::time::Duration
     pub async fn shutdown_with_limit(
-        mut self,
+        self,
         limit: time::Duration,
-    ) -> Result<time::Duration, TimeoutError> {
+    ) -> Result<ShutdownReport, TimeoutError> {
+        let report = self.wait_for_shutdown(Some(limit)).await;
+        if report.is_graceful() {
+            Ok(report)
+        } else {
+            Err(TimeoutError(Box::new(report)))
+        }
+    }
+
+    async fn wait_for_shutdown(mut self, limit: Option<time::Duration>) -> ShutdownReport {
         tracing::info!("::shutdown: waiting for signal to trigger (read: to be cancelled)");
         let weak_guard = self.guard.downgrade();
+        let shutdown_trigger = weak_guard.shutdown_trigger();
         let start: time::Instant = time::Instant::now();
         tokio::select! {
             _ = weak_guard.cancelled() => {
-                tracing::info!(
-                    "::shutdown: waiting for all guards to drop for a max of {}s",
-                    limit.as_secs_f64()
-                );
+                shutdown_trigger.readiness.advance(ReadinessState::Draining);
+                // a shutdown without a signal is cancelled from the start
+                shutdown_trigger.record_cancelled();
+                shutdown_trigger.lifecycle.advance(Lifecycle::Draining {
+                    remaining_guards: weak_guard.ref_count.load(Ordering::SeqCst),
+                });
+                #[cfg(all(feature = "systemd", unix))]
+                if let Some(limit) = limit {
+                    shutdown_trigger.extend_timeout(limit);
+                }
+                match limit {
+                    Some(limit) => tracing::info!(
+                        "::shutdown: waiting for all guards to drop for a max of {}s",
+                        limit.as_secs_f64()
+                    ),
+                    None => tracing::info!("::shutdown: waiting for all guards to drop"),
+                }
             }
             _ = &mut self.zero_overwrite_rx => {
                 let elapsed = start.elapsed();
                 tracing::warn!("::shutdown: enforced: overwrite delayed cancellation after {}s", elapsed.as_secs_f64());
-                return Err(TimeoutError(elapsed));
+                Phase::cancel_all(self.phases);
+                shutdown_trigger.readiness.advance(ReadinessState::Stopped);
+                shutdown_trigger.lifecycle.advance(Lifecycle::Overwritten);
+                let drain = DrainStats {
+                    elapsed: Duration::ZERO,
+                    outcome: DrainOutcome::Overwritten,
+                    live_guards: weak_guard.registry.snapshot(),
+                    aborted_tasks: 0,
+                };
+                return shutdown_trigger.report(drain, Vec::new());
             }
         };
 
         let start: time::Instant = time::Instant::now();
-        tokio::select! {
-            _ = tokio::time::sleep(limit) => {
-                tracing::info!("::shutdown: timeout after {}s", limit.as_secs_f64());
-                Err(TimeoutError(limit))
+        let limit = async move {
+            match limit {
+                Some(limit) => tokio::time::sleep(limit).await,
+                None => std::future::pending().await,
+            }
+        };
+        #[cfg(feature = "leak-detection")]
+        let leak_check = shutdown_trigger
+            .leak_detector()
+            .map(|leak_detector| leak_detector.spawn_check(weak_guard.registry.scope()));
+        let progress = report_drain_progress(false, &weak_guard, self.drain_progress, start);
+        let outcome = tokio::select! {
+            never = progress => match never {},
+            _ = limit => {
+                tracing::info!("::shutdown: timeout after {}s", start.elapsed().as_secs_f64());
+                DrainOutcome::TimedOut
             }
             _ = self.zero_rx => {
-                let elapsed = start.elapsed();
-                tracing::info!("::shutdown: ready after {}s", elapsed.as_secs_f64());
-                Ok(elapsed)
+                tracing::info!("::shutdown: ready after {}s", start.elapsed().as_secs_f64());
+                DrainOutcome::Completed
             }
-            _ = self.zero_overwrite_rx => {
-                let elapsed = start.elapsed();
-                tracing::warn!("::shutdown: enforced: overwrite signal triggered after {}s", elapsed.as_secs_f64());
-                Err(TimeoutError(elapsed))
+            _ = &mut self.zero_overwrite_rx => {
+                tracing::warn!("::shutdown: enforced: overwrite signal triggered after {}s", start.elapsed().as_secs_f64());
+                DrainOutcome::Overwritten
             }
-        }
+        };
+        let elapsed = start.elapsed();
+        #[cfg(feature = "leak-detection")]
+        drop(leak_check);
+        let live_guards = match outcome {
+            DrainOutcome::Completed => Vec::new(),
+            _ => {
+                let live_guards = weak_guard.registry.snapshot();
+                for info in &live_guards {
+                    tracing::warn!("::shutdown: guard still alive: {}", info);
+                }
+                live_guards
+            }
+        };
+        let aborted_tasks = match (outcome, &weak_guard.task_tracker) {
+            (DrainOutcome::TimedOut, Some(task_tracker)) => {
+                shutdown_trigger.abort_tasks(task_tracker).await
+            }
+            _ => 0,
+        };
+        let drain = DrainStats {
+            elapsed,
+            outcome,
+            live_guards,
+            aborted_tasks,
+        };
+
+        let phases = if outcome == DrainOutcome::Overwritten {
+            Phase::cancel_all(self.phases);
+            Vec::new()
+        } else {
+            if !self.phases.is_empty() {
+                shutdown_trigger.lifecycle.advance(Lifecycle::RunningPhases);
+            }
+            Phase::run_all(self.phases, &mut self.zero_overwrite_rx).await
+        };
+
+        let report = shutdown_trigger.report(drain, phases);
+        // the final state takes the phases into account,
+        // as the shutdown is only done once its phases are done as well
+        shutdown_trigger.readiness.advance(ReadinessState::Stopped);
+        shutdown_trigger
+            .lifecycle
+            .advance(match report.final_outcome() {
+                DrainOutcome::Completed => Lifecycle::Completed,
+                DrainOutcome::TimedOut => Lifecycle::TimedOut,
+                DrainOutcome::Overwritten => Lifecycle::Overwritten,
+            });
+        report
     }
 }
 
-/// Returns a [`Future`] that completes once one of the default signals.
+/// A [`ShutdownTrigger`] can be used to manually trigger
+/// the shutdown of the [`Shutdown`] it is linked to.
 ///
-/// Which on Unix is Ctrl-C (sigint) or sigterm,
-/// and on Windows is Ctrl-C, Ctrl-Close or Ctrl-Shutdown.
+/// Triggering it starts the same shutdown sequence as the
+/// shutdown signal would, including the delay and overwrite fn (if any).
 ///
-/// Exposed to you so you can easily expand it by for example
-/// chaining it with a [`tokio::time::sleep`] to have a delay
-/// before shutdown is triggered.
+/// It can be obtained using [`Shutdown::shutdown_trigger`],
+/// [`ShutdownGuard::shutdown_trigger`] or [`WeakShutdownGuard::shutdown_trigger`],
+/// and can be cloned cheaply.
 ///
-/// [`Future`]: std::future::Future
-/// [`tokio::time::sleep`]: https://docs.rs/tokio/*/tokio/time/fn.sleep.html
-#[cfg(all(not(loom), any(unix, windows)))]
-pub async fn default_signal() {
-    let ctrl_c = tokio::signal::ctrl_c();
-    #[cfg(all(unix, not(windows)))]
-    {
-        let signal = async {
-            let mut os_signal =
-                tokio::signal::unix::signal(tokio::signal::unix::SignalKind::terminate())?;
-            os_signal.recv().await;
-            std::io::Result::Ok(())
+/// [`ShutdownGuard::shutdown_trigger`]: crate::ShutdownGuard::shutdown_trigger
+/// [`WeakShutdownGuard::shutdown_trigger`]: crate::WeakShutdownGuard::shutdown_trigger
+#[derive(Debug, Clone)]
+pub struct ShutdownTrigger {
+    tx: Sender,
+    state: Arc<Mutex<TriggerState>>,
+    readiness: Readiness,
+    lifecycle: LifecycleSender,
+    timeline: Option<TimelineRecorder>,
+    #[cfg(feature = "leak-detection")]
+    leak_detector: Option<LeakDetector>,
+}
+
+/// The state shared between all clones of a [`ShutdownTrigger`],
+/// used to report on the shutdown once it is complete.
+#[derive(Debug, Default)]
+struct TriggerState {
+    reason: Option<ShutdownReason>,
+    triggered_at: Option<time::Instant>,
+    cancelled_at: Option<time::Instant>,
+}
+
+impl ShutdownTrigger {
+    fn new(tx: Sender, options: &ShutdownOptions) -> Self {
+        Self {
+            tx,
+            state: Default::default(),
+            readiness: options.readiness(),
+            lifecycle: LifecycleSender::default(),
+            timeline: options.timeline.then(TimelineRecorder::default),
+            #[cfg(feature = "leak-detection")]
+            leak_detector: options.leak_detection.map(LeakDetector::new),
+        }
+    }
+
+    /// Triggers the shutdown of the linked [`Shutdown`].
+    ///
+    /// Triggering an already triggered [`Shutdown`] has no effect,
+    /// and neither has triggering a [`Shutdown`] without a signal,
+    /// as such a [`Shutdown`] is always considered to be triggered.
+    ///
+    /// # Panics
+    ///
+    /// This method can panic if the internal mutex is poisoned.
+    pub fn trigger(&self) {
+        self.trigger_with_reason(ShutdownReason::Manual);
+    }
+
+    /// Triggers the shutdown of the linked [`Shutdown`],
+    /// recording the given reason unless a reason was already recorded.
+    pub(crate) fn trigger_with_reason(&self, reason: ShutdownReason) {
+        tracing::trace!("::trigger: shutdown triggered: {}", reason);
+        self.record_reason(reason);
+        self.tx.trigger();
+    }
+
+    /// Records the reason of the shutdown, only the first recorded reason is kept.
+    pub(crate) fn record_reason(&self, reason: ShutdownReason) {
+        let mut state = self.state.lock().unwrap();
+        if state.reason.is_none() {
+            if let Some(timeline) = &self.timeline {
+                timeline.record(TimelineEventKind::SignalReceived(reason.clone()));
+            }
+            state.reason = Some(reason);
+            state.triggered_at = Some(time::Instant::now());
+        }
+        drop(state);
+        self.readiness.advance(ReadinessState::Draining);
+    }
+
+    /// Records the moment the guards were cancelled,
+    /// which is after the delay (if any) has been awaited.
+    pub(crate) fn record_cancelled(&self) {
+        let mut state = self.state.lock().unwrap();
+        if state.cancelled_at.is_none() {
+            if let Some(timeline) = &self.timeline {
+                if state.triggered_at.is_some() {
+                    timeline.record(TimelineEventKind::DelayEnded);
+                }
+                timeline.record(TimelineEventKind::Cancelled);
+            }
+            state.cancelled_at = Some(time::Instant::now());
+        }
+        drop(state);
+        self.lifecycle.advance(Lifecycle::Cancelled);
+    }
+
+    /// Returns the time elapsed since the guards were cancelled,
+    /// or [`Duration::ZERO`] if they have not been cancelled (yet).
+    pub(crate) fn elapsed_since_cancelled(&self) -> Duration {
+        self.state
+            .lock()
+            .unwrap()
+            .cancelled_at
+            .map(|cancelled_at| cancelled_at.elapsed())
+            .unwrap_or_default()
+    }
+
+    /// Records that the shutdown signal was received (or that the shutdown
+    /// was triggered manually), after which the given delay is awaited.
+    pub(crate) fn record_signal_received(&self, delay: Duration) {
+        if let Some(timeline) = &self.timeline {
+            timeline.record(TimelineEventKind::DelayStarted(delay));
+        }
+        self.lifecycle.advance(Lifecycle::SignalReceived(delay));
+    }
+
+    /// Creates the [`ShutdownReport`] using the recorded state of the shutdown.
+    fn report(&self, drain: DrainStats, phases: Vec<PhaseReport>) -> ShutdownReport {
+        let state = self.state.lock().unwrap();
+        let now = time::Instant::now();
+        let delay = match (state.triggered_at, state.cancelled_at) {
+            (Some(triggered_at), Some(cancelled_at)) => cancelled_at - triggered_at,
+            (Some(triggered_at), None) => now - triggered_at,
+            (None, _) => Duration::ZERO,
         };
-        tokio::select! {
-            _ = ctrl_c => {}
-            _ = signal => {}
+        let overwritten = drain.outcome == DrainOutcome::Overwritten
+            || phases
+                .last()
+                .map(|phase| phase.outcome() == DrainOutcome::Overwritten)
+                .unwrap_or_default();
+        let overwrite = overwritten.then(|| {
+            state
+                .triggered_at
+                .map(|triggered_at| now - triggered_at)
+                .unwrap_or_default()
+        });
+        let mut report = ShutdownReport::new(state.reason.clone(), delay, overwrite, drain, phases);
+        if let Some(timeline) = self.timeline.as_ref() {
+            timeline.record(TimelineEventKind::Finished(report.final_outcome()));
+            report = report.with_timeline(timeline.timeline());
         }
+        #[cfg(feature = "metrics")]
+        crate::metrics::record_report(&report);
+        report
     }
-    #[cfg(all(not(unix), windows))]
-    {
-        let ctrl_close = async {
-            let mut signal = tokio::signal::windows::ctrl_close()?;
-            signal.recv().await;
-            std::io::Result::Ok(())
+
+    /// Returns the reason why the shutdown was triggered,
+    /// or `None` if the shutdown has not been triggered yet.
+    ///
+    /// # Panics
+    ///
+    /// This method can panic if the internal mutex is poisoned.
+    pub fn reason(&self) -> Option<ShutdownReason> {
+        self.state.lock().unwrap().reason.clone()
+    }
+
+    pub(crate) fn readiness(&self) -> Readiness {
+        self.readiness.clone()
+    }
+
+    /// Asks the systemd service manager to extend the stop timeout of the service
+    /// by the given time limit of the current stage of the shutdown,
+    /// in case it is notified (see [`ShutdownBuilder::with_systemd_notify`]).
+    #[cfg(all(feature = "systemd", unix))]
+    fn extend_timeout(&self, timeout: Duration) {
+        if self.readiness.systemd_notify() {
+            crate::systemd::notify_extend_timeout(timeout);
+        }
+    }
+
+    /// Aborts the tasks of the given tracker once the time limit (or budget) was reached,
+    /// returning the amount of tasks aborted once all of them are confirmed to be dropped.
+    async fn abort_tasks(&self, task_tracker: &TaskTracker) -> usize {
+        #[cfg(all(feature = "systemd", unix))]
+        self.extend_timeout(crate::systemd::ABORT_TIMEOUT);
+        task_tracker.abort_all().await
+    }
+
+    pub(crate) fn lifecycle(&self) -> &LifecycleSender {
+        &self.lifecycle
+    }
+
+    pub(crate) fn timeline(&self) -> Option<&TimelineRecorder> {
+        self.timeline.as_ref()
+    }
+
+    #[cfg(feature = "leak-detection")]
+    pub(crate) fn leak_detector(&self) -> Option<&LeakDetector> {
+        self.leak_detector.as_ref()
+    }
+}
+
+#[derive(Debug, Default)]
+struct ShutdownOptions {
+    phases: Vec<PhaseOptions>,
+    abort_on_timeout: bool,
+    reload_trigger: Option<ReloadTrigger>,
+    #[cfg(all(not(loom), unix))]
+    reload_signals: Vec<tokio::signal::unix::SignalKind>,
+    #[cfg(all(feature = "systemd", unix))]
+    systemd_notify: bool,
+    timeline: bool,
+    #[cfg(feature = "leak-detection")]
+    leak_detection: Option<Duration>,
+    drain_progress: Option<Duration>,
+    parent: Option<WeakShutdownGuard>,
+    /// The scope of a child [`Shutdown`], see [`Registry::child_scope`].
+    scope: Option<Cow<'static, str>>,
+}
+
+impl ShutdownOptions {
+    /// Inherits the options of the given parent which also apply to its children,
+    /// see [`ShutdownGuard::child`] for more information.
+    ///
+    /// [`ShutdownGuard::child`]: crate::ShutdownGuard::child
+    fn inherit(&mut self, parent: &WeakShutdownGuard) {
+        self.abort_on_timeout = parent.task_tracker.is_some();
+        self.timeline = parent.shutdown_trigger.timeline.is_some();
+        #[cfg(feature = "leak-detection")]
+        {
+            self.leak_detection = parent
+                .shutdown_trigger
+                .leak_detector()
+                .map(LeakDetector::threshold);
+        }
+    }
+
+    fn registry(&self) -> Registry {
+        match &self.scope {
+            Some(scope) => Registry::new(scope.clone()),
+            None => Registry::default(),
+        }
+    }
+
+    fn task_tracker(&self) -> Option<TaskTracker> {
+        self.abort_on_timeout.then(TaskTracker::default)
+    }
+
+    #[cfg_attr(not(all(feature = "systemd", unix)), allow(clippy::unused_self))]
+    fn readiness(&self) -> Readiness {
+        #[cfg(all(feature = "systemd", unix))]
+        if self.systemd_notify {
+            return Readiness::default().with_systemd_notify();
+        }
+        Readiness::default()
+    }
+}
+
+#[derive(Debug)]
+struct PhaseOptions {
+    name: Cow<'static, str>,
+    budget: Duration,
+}
+
+/// A phase of a [`Shutdown`], which is run once
+/// the guards of the [`Shutdown`] (or its previous phase) have been dropped.
+struct Phase {
+    name: Cow<'static, str>,
+    budget: Duration,
+    drain_progress: Option<Duration>,
+    guard: ShutdownGuard,
+    cancel_tx: Sender,
+    zero_rx: Receiver,
+    task_tracker: Option<TaskTracker>,
+}
+
+impl Phase {
+    fn new(
+        options: PhaseOptions,
+        scope: Cow<'static, str>,
+        drain_progress: Option<Duration>,
+        shutdown_trigger: ShutdownTrigger,
+        task_tracker: Option<TaskTracker>,
+        reload_trigger: ReloadTrigger,
+    ) -> Self {
+        let (cancel_tx, cancel_rx) = trigger();
+        let (zero_tx, zero_rx) = trigger();
+        // each phase has its own registry, such that only
+        // the guards of the phase are reported once it times out
+        let guard = ShutdownGuard::new(
+            cancel_rx,
+            None,
+            zero_tx,
+            Default::default(),
+            shutdown_trigger,
+            Registry::new(scope),
+            task_tracker.clone(),
+            reload_trigger,
+        );
+        Self {
+            name: options.name,
+            budget: options.budget,
+            drain_progress,
+            guard,
+            cancel_tx,
+            zero_rx,
+            task_tracker,
+        }
+    }
+
+    fn build_all(
+        options: ShutdownOptions,
+        shutdown_trigger: &ShutdownTrigger,
+        reload_trigger: &ReloadTrigger,
+    ) -> Vec<Self> {
+        let ShutdownOptions {
+            phases,
+            abort_on_timeout,
+            drain_progress,
+            scope,
+            ..
+        } = options;
+        phases
+            .into_iter()
+            .map(|phase_options| {
+                // the phases of a child are nested within the scope of the child
+                let scope = match &scope {
+                    Some(scope) => Cow::Owned(format!("{}/{}", scope, phase_options.name)),
+                    None => phase_options.name.clone(),
+                };
+                Self::new(
+                    phase_options,
+                    scope,
+                    drain_progress,
+                    shutdown_trigger.clone(),
+                    abort_on_timeout.then(TaskTracker::default),
+                    reload_trigger.clone(),
+                )
+            })
+            .collect()
+    }
+
+    /// Cancels all given phases without waiting for their guards,
+    /// used when the shutdown was enforced by the overwrite signal.
+    fn cancel_all(phases: Vec<Self>) {
+        for phase in phases {
+            phase.cancel_tx.trigger();
+        }
+    }
+
+    /// Runs all given phases in order, stopping early
+    /// (but still cancelling the remaining phases) when
+    /// the overwrite signal is triggered.
+    async fn run_all(phases: Vec<Self>, zero_overwrite_rx: &mut Receiver) -> Vec<PhaseReport> {
+        let mut reports = Vec::with_capacity(phases.len());
+        let mut phases = phases.into_iter();
+        for phase in phases.by_ref() {
+            let report = phase.run(zero_overwrite_rx).await;
+            let overwritten = report.outcome() == DrainOutcome::Overwritten;
+            reports.push(report);
+            if overwritten {
+                break;
+            }
+        }
+        Self::cancel_all(phases.collect());
+        reports
+    }
+
+    async fn run(self, zero_overwrite_rx: &mut Receiver) -> PhaseReport {
+        let Self {
+            name,
+            budget,
+            drain_progress,
+            guard,
+            cancel_tx,
+            zero_rx,
+            task_tracker,
+        } = self;
+
+        tracing::info!(
+            "::shutdown: phase '{}': waiting for all guards to drop for a max of {}s",
+            name,
+            budget.as_secs_f64()
+        );
+        let timeline = guard.shutdown_trigger().timeline;
+        if let Some(timeline) = &timeline {
+            timeline.record(TimelineEventKind::PhaseStarted { name: name.clone() });
+        }
+        let weak_guard = guard.weak().clone();
+        #[cfg(feature = "leak-detection")]
+        let leak_check = weak_guard
+            .shutdown_trigger
+            .leak_detector()
+            .map(|leak_detector| leak_detector.spawn_check(weak_guard.registry.scope()));
+        drop(guard);
+        cancel_tx.trigger();
+        #[cfg(all(feature = "systemd", unix))]
+        weak_guard.shutdown_trigger.extend_timeout(budget);
+
+        let start: time::Instant = time::Instant::now();
+        let progress = report_drain_progress(true, &weak_guard, drain_progress, start);
+        let outcome = tokio::select! {
+            never = progress => match never {},
+            _ = tokio::time::sleep(budget) => {
+                tracing::warn!("::shutdown: phase '{}': timeout after {}s", name, budget.as_secs_f64());
+                DrainOutcome::TimedOut
+            }
+            _ = zero_rx => {
+                tracing::info!("::shutdown: phase '{}': ready after {}s", name, start.elapsed().as_secs_f64());
+                DrainOutcome::Completed
+            }
+            _ = zero_overwrite_rx => {
+                tracing::warn!("::shutdown: phase '{}': enforced: overwrite signal triggered after {}s", name, start.elapsed().as_secs_f64());
+                DrainOutcome::Overwritten
+            }
         };
-        let ctrl_shutdown = async {
-            let mut signal = tokio::signal::windows::ctrl_shutdown()?;
-            signal.recv().await;
-            std::io::Result::Ok(())
+
+        let elapsed = start.elapsed();
+        #[cfg(feature = "leak-detection")]
+        drop(leak_check);
+        let live_guards = match outcome {
+            DrainOutcome::Completed => Vec::new(),
+            _ => {
+                let live_guards = weak_guard.registry.snapshot();
+                for info in &live_guards {
+                    tracing::warn!("::shutdown: phase '{}': guard still alive: {}", name, info);
+                }
+                live_guards
+            }
         };
-        tokio::select! {
-            _ = ctrl_c => {}
-            _ = ctrl_close => {}
-            _ = ctrl_shutdown => {}
+        let aborted_tasks = match (outcome, task_tracker) {
+            (DrainOutcome::TimedOut, Some(task_tracker)) => {
+                weak_guard.shutdown_trigger.abort_tasks(&task_tracker).await
+            }
+            _ => 0,
+        };
+
+        if let Some(timeline) = &timeline {
+            timeline.record(TimelineEventKind::PhaseEnded {
+                name: name.clone(),
+                outcome,
+            });
         }
+        PhaseReport::new(name, elapsed, outcome, live_guards, aborted_tasks)
+    }
+}
+
+/// Reports the progress of a drain every given interval (if any), until dropped,
+/// either of the [`Shutdown`] itself or of one of its phases.
+///
+/// The amount of remaining guards and the named guards still alive are both taken
+/// from the given guard, such that they concern the guards of the same drain.
+/// The drain is labeled with the scope of its registry, unless it is the main one,
+/// such that the drains of a child [`Shutdown`] can be told apart from those of its parent.
+async fn report_drain_progress(
+    phase: bool,
+    weak_guard: &WeakShutdownGuard,
+    interval: Option<Duration>,
+    start: time::Instant,
+) -> Infallible {
+    let Some(interval) = interval else {
+        return std::future::pending().await;
+    };
+    let scope = if phase {
+        format!(" phase '{}':", weak_guard.registry.scope())
+    } else if weak_guard.registry.is_main() {
+        String::new()
+    } else {
+        format!(" scope '{}':", weak_guard.registry.scope())
+    };
+    loop {
+        tokio::time::sleep(interval).await;
+        let live_guards: Vec<_> = weak_guard
+            .registry
+            .snapshot()
+            .into_iter()
+            .map(|info| info.name().to_owned())
+            .collect();
+        tracing::info!(
+            "::shutdown:{} still draining after {}s: {} guard(s) remaining{}{}",
+            scope,
+            start.elapsed().as_secs_f64(),
+            weak_guard.ref_count.load(Ordering::SeqCst),
+            if live_guards.is_empty() {
+                ""
+            } else {
+                ", still alive: "
+            },
+            live_guards.join(", ")
+        );
     }
 }
 
 #[cfg(all(not(loom), any(unix, windows)))]
 impl Default for Shutdown {
     fn default() -> Self {
-        Self::new(default_signal())
+        ShutdownBuilder::default().build()
     }
 }
 
+/// The error returned by [`Shutdown::shutdown_with_limit`]
+/// in case the limit was reached, a phase exceeded its time budget
+/// or the overwrite signal was triggered before all [`ShutdownGuard`]s were dropped.
+///
+/// [`ShutdownGuard`]: crate::ShutdownGuard
 #[derive(Debug)]
-pub struct TimeoutError(time::Duration);
+pub struct TimeoutError(Box<ShutdownReport>);
+
+impl TimeoutError {
+    /// Returns the [`ShutdownReport`] of the shutdown which timed out.
+    ///
+    /// [`ShutdownReport`]: crate::ShutdownReport
+    pub fn report(&self) -> &ShutdownReport {
+        &self.0
+    }
+
+    /// Returns the info of the named [`ShutdownGuard`]s
+    /// which were still alive when the shutdown timed out.
+    ///
+    /// See [`ShutdownReport::live_guards`] for more information.
+    ///
+    /// [`ShutdownGuard`]: crate::ShutdownGuard
+    /// [`ShutdownReport::live_guards`]: crate::ShutdownReport::live_guards
+    pub fn live_guards(&self) -> &[GuardInfo] {
+        self.0.live_guards()
+    }
+
+    /// Consumes the error, returning the [`ShutdownReport`]
+    /// of the shutdown which timed out.
+    ///
+    /// [`ShutdownReport`]: crate::ShutdownReport
+    pub fn into_report(self) -> ShutdownReport {
+        *self.0
+    }
+}
 
 impl std::fmt::Display for TimeoutError {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        write!(f, "timeout after {}s", self.0.as_secs_f64())
+        let phase = self
+            .0
+            .phases()
+            .iter()
+            .find(|phase| phase.outcome() != DrainOutcome::Completed);
+        match (self.0.outcome(), phase) {
+            (DrainOutcome::Overwritten, _) => write!(
+                f,
+                "overwritten after {}s",
+                self.0.overwrite().unwrap_or_default().as_secs_f64()
+            ),
+            (DrainOutcome::Completed, Some(phase))
+                if phase.outcome() == DrainOutcome::Overwritten =>
+            {
+                write!(
+                    f,
+                    "phase '{}' overwritten after {}s",
+                    phase.name(),
+                    self.0.overwrite().unwrap_or_default().as_secs_f64()
+                )
+            }
+            (DrainOutcome::Completed, Some(phase)) => write!(
+                f,
+                "phase '{}' timeout after {}s",
+                phase.name(),
+                phase.elapsed().as_secs_f64()
+            ),
+            _ => write!(f, "timeout after {}s", self.0.drain().as_secs_f64()),
+        }
     }
 }
 
@@ -542,31 +1745,78 @@ impl std::error::Error for TimeoutError {}
 mod sealed {
     use std::{fmt, future::Future, time::Duration};
 
+    use crate::ShutdownReason;
+
     pub trait IntoFuture: Send + 'static {
-        fn into_future(self) -> impl Future + Send + 'static;
+        fn into_future(self) -> impl Future<Output = ShutdownReason> + Send + 'static;
     }
 
     impl<F> IntoFuture for F
     where
         F: Future + Send + 'static,
     {
-        fn into_future(self) -> impl Future + Send + 'static {
-            self
+        #[allow(clippy::manual_async_fn)]
+        fn into_future(self) -> impl Future<Output = ShutdownReason> + Send + 'static {
+            async move {
+                let _ = self.await;
+                ShutdownReason::Signal
+            }
         }
     }
 
-    #[derive(Debug)]
+    #[derive(Debug, Default)]
     #[non_exhaustive]
-    pub struct Default;
+    pub struct Default {
+        #[cfg_attr(loom, allow(dead_code))]
+        pub(super) signals: crate::signal::SignalSet,
+        #[cfg(all(not(loom), any(unix, windows)))]
+        pub(super) listener: crate::signal::SharedListener,
+    }
 
     impl IntoFuture for Default {
         #[cfg(loom)]
-        fn into_future(self) -> impl Future + Send + 'static {
-            std::future::pending::<()>()
+        fn into_future(self) -> impl Future<Output = ShutdownReason> + Send + 'static {
+            std::future::pending()
         }
         #[cfg(not(loom))]
-        fn into_future(self) -> impl Future + Send + 'static {
-            super::default_signal()
+        #[allow(clippy::manual_async_fn)]
+        fn into_future(self) -> impl Future<Output = ShutdownReason> + Send + 'static {
+            async move {
+                match self.signals.listen() {
+                    Ok(mut listener) => {
+                        let signal = listener.recv().await;
+                        // keep the signals registered for a possible escalation
+                        *self.listener.lock().unwrap_or_else(|err| err.into_inner()) =
+                            Some(listener);
+                        ShutdownReason::OsSignal(signal)
+                    }
+                    Err(err) => {
+                        tracing::warn!("::trigger: failed to register signal: {}", err);
+                        ShutdownReason::Signal
+                    }
+                }
+            }
+        }
+    }
+
+    pub trait IntoOverwriteFuture: Send + 'static {
+        fn into_overwrite_future(self) -> impl Future + Send + 'static;
+    }
+
+    impl<F, Fut> IntoOverwriteFuture for F
+    where
+        F: FnOnce() -> Fut + Send + 'static,
+        Fut: Future + Send + 'static,
+    {
+        fn into_overwrite_future(self) -> impl Future + Send + 'static {
+            self()
+        }
+    }
+
+    #[cfg(all(not(loom), any(unix, windows)))]
+    impl IntoOverwriteFuture for crate::Escalation {
+        fn into_overwrite_future(self) -> impl Future + Send + 'static {
+            self.into_remaining_signals()
         }
     }
 
@@ -0,0 +1,322 @@
+use std::fmt;
+
+/// An operating system signal which triggered a shutdown,
+/// recorded as part of the [`ShutdownReason`].
+///
+/// [`ShutdownReason`]: crate::ShutdownReason
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum OsSignal {
+    /// A Unix signal, e.g. SIGINT (Ctrl-C) or SIGTERM.
+    #[cfg(all(not(loom), unix))]
+    Unix(tokio::signal::unix::SignalKind),
+    /// The Windows Ctrl-C signal.
+    #[cfg(all(not(loom), windows))]
+    CtrlC,
+    /// The Windows Ctrl-Close signal.
+    #[cfg(all(not(loom), windows))]
+    CtrlClose,
+    /// The Windows Ctrl-Shutdown signal.
+    #[cfg(all(not(loom), windows))]
+    CtrlShutdown,
+}
+
+impl fmt::Display for OsSignal {
+    #[cfg_attr(loom, allow(unused_variables))]
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match *self {
+            #[cfg(all(not(loom), unix))]
+            OsSignal::Unix(kind) => {
+                use tokio::signal::unix::SignalKind;
+
+                let name = [
+                    (SignalKind::interrupt(), "SIGINT"),
+                    (SignalKind::terminate(), "SIGTERM"),
+                    (SignalKind::hangup(), "SIGHUP"),
+                    (SignalKind::quit(), "SIGQUIT"),
+                    (SignalKind::user_defined1(), "SIGUSR1"),
+                    (SignalKind::user_defined2(), "SIGUSR2"),
+                ]
+                .into_iter()
+                .find_map(|(known, name)| (known == kind).then_some(name));
+                match name {
+                    Some(name) => write!(f, "{}", name),
+                    None => write!(f, "signal {}", kind.as_raw_value()),
+                }
+            }
+            #[cfg(all(not(loom), windows))]
+            OsSignal::CtrlC => write!(f, "Ctrl-C"),
+            #[cfg(all(not(loom), windows))]
+            OsSignal::CtrlClose => write!(f, "Ctrl-Close"),
+            #[cfg(all(not(loom), windows))]
+            OsSignal::CtrlShutdown => write!(f, "Ctrl-Shutdown"),
+        }
+    }
+}
+
+/// The set of operating system signals which trigger a shutdown.
+#[derive(Debug, Clone, Default)]
+pub(crate) enum SignalSet {
+    /// The default signals, see [`default_signal`].
+    #[default]
+    Default,
+    /// A custom set of Unix signals,
+    /// see [`ShutdownBuilder::with_unix_signals`].
+    ///
+    /// [`ShutdownBuilder::with_unix_signals`]: crate::ShutdownBuilder::with_unix_signals
+    #[cfg(all(not(loom), unix))]
+    Unix(Vec<tokio::signal::unix::SignalKind>),
+}
+
+impl SignalSet {
+    /// Registers the signals of this set, returning a [`SignalListener`]
+    /// which can be used to receive them, as many times as needed.
+    ///
+    /// An error is returned in case one of the signal handlers
+    /// could not be registered.
+    #[cfg(all(not(loom), any(unix, windows)))]
+    pub(crate) fn listen(&self) -> std::io::Result<SignalListener> {
+        match self {
+            SignalSet::Default => SignalListener::default_signals(),
+            #[cfg(unix)]
+            SignalSet::Unix(kinds) => Ok(SignalListener::Unix(UnixSignals::new(kinds)?)),
+        }
+    }
+}
+
+/// The registered signals of a [`SignalSet`].
+///
+/// Signals received while not polling the listener are not lost,
+/// but will be returned by the next call to [`SignalListener::recv`].
+#[cfg(all(not(loom), any(unix, windows)))]
+#[derive(Debug)]
+pub(crate) enum SignalListener {
+    #[cfg(unix)]
+    Unix(UnixSignals),
+    #[cfg(all(not(unix), windows))]
+    Windows {
+        ctrl_c: tokio::signal::windows::CtrlC,
+        ctrl_close: tokio::signal::windows::CtrlClose,
+        ctrl_shutdown: tokio::signal::windows::CtrlShutdown,
+    },
+}
+
+#[cfg(all(not(loom), any(unix, windows)))]
+impl SignalListener {
+    /// Registers the default signals (see [`default_signal`]).
+    fn default_signals() -> std::io::Result<Self> {
+        #[cfg(unix)]
+        {
+            use tokio::signal::unix::SignalKind;
+
+            Ok(SignalListener::Unix(UnixSignals::new(&[
+                SignalKind::interrupt(),
+                SignalKind::terminate(),
+            ])?))
+        }
+        #[cfg(all(not(unix), windows))]
+        {
+            Ok(SignalListener::Windows {
+                ctrl_c: tokio::signal::windows::ctrl_c()?,
+                ctrl_close: tokio::signal::windows::ctrl_close()?,
+                ctrl_shutdown: tokio::signal::windows::ctrl_shutdown()?,
+            })
+        }
+    }
+
+    /// Waits for one of the registered signals to be received,
+    /// returning the [`OsSignal`] which was received.
+    ///
+    /// This method is cancel safe.
+    pub(crate) async fn recv(&mut self) -> OsSignal {
+        match self {
+            #[cfg(unix)]
+            SignalListener::Unix(signals) => signals.recv().await,
+            #[cfg(all(not(unix), windows))]
+            SignalListener::Windows {
+                ctrl_c,
+                ctrl_close,
+                ctrl_shutdown,
+            } => {
+                std::future::poll_fn(|cx| {
+                    if let std::task::Poll::Ready(Some(())) = ctrl_c.poll_recv(cx) {
+                        return std::task::Poll::Ready(OsSignal::CtrlC);
+                    }
+                    if let std::task::Poll::Ready(Some(())) = ctrl_close.poll_recv(cx) {
+                        return std::task::Poll::Ready(OsSignal::CtrlClose);
+                    }
+                    if let std::task::Poll::Ready(Some(())) = ctrl_shutdown.poll_recv(cx) {
+                        return std::task::Poll::Ready(OsSignal::CtrlShutdown);
+                    }
+                    std::task::Poll::Pending
+                })
+                .await
+            }
+        }
+    }
+}
+
+/// The [`SignalListener`] which received the signal triggering the shutdown,
+/// handed over to the [`Escalation`] such that it keeps polling the same signals.
+#[cfg(all(not(loom), any(unix, windows)))]
+pub(crate) type SharedListener = std::sync::Arc<std::sync::Mutex<Option<SignalListener>>>;
+
+/// Escalation of the default signals, used to force a shutdown
+/// when the default signal is received multiple times,
+/// e.g. a second Ctrl-C skipping the delay and the waiting for the guards.
+///
+/// See [`ShutdownBuilder::with_escalation`] for more information.
+///
+/// [`ShutdownBuilder::with_escalation`]: crate::ShutdownBuilder::with_escalation
+#[cfg(all(not(loom), any(unix, windows)))]
+#[derive(Debug, Clone)]
+pub struct Escalation {
+    count: usize,
+    message: Option<std::borrow::Cow<'static, str>>,
+    signals: SignalSet,
+    listener: SharedListener,
+}
+
+#[cfg(all(not(loom), any(unix, windows)))]
+impl Default for Escalation {
+    fn default() -> Self {
+        Self::new(2)
+    }
+}
+
+#[cfg(all(not(loom), any(unix, windows)))]
+impl Escalation {
+    /// Creates a new [`Escalation`] which forces the shutdown once
+    /// the default signal is received for the given amount of times,
+    /// including the signal which triggered the shutdown.
+    ///
+    /// A count of `2` (the default) means that the second signal forces the shutdown,
+    /// while a count of `0` or `1` forces the shutdown as soon as it is triggered.
+    pub fn new(count: usize) -> Self {
+        Self {
+            count,
+            message: None,
+            signals: SignalSet::Default,
+            listener: SharedListener::default(),
+        }
+    }
+
+    /// Attach a message to this [`Escalation`], logged (as a warning)
+    /// once the shutdown is triggered, e.g. "press Ctrl-C again to force".
+    pub fn with_message(mut self, message: impl Into<std::borrow::Cow<'static, str>>) -> Self {
+        self.message = Some(message.into());
+        self
+    }
+
+    /// Returns the amount of times the default signal has to be received
+    /// for the shutdown to be forced.
+    pub fn count(&self) -> usize {
+        self.count
+    }
+
+    /// Returns the message (if any) logged once the shutdown is triggered.
+    pub fn message(&self) -> Option<&str> {
+        self.message.as_deref()
+    }
+
+    /// Use the given signals instead of the default signals,
+    /// such that the escalation listens to the same signals
+    /// as the ones which trigger the shutdown.
+    ///
+    /// The listener which received the triggering signal is taken over,
+    /// such that no signal is missed in between.
+    pub(crate) fn with_signals(mut self, signals: SignalSet, listener: SharedListener) -> Self {
+        self.signals = signals;
+        self.listener = listener;
+        self
+    }
+
+    /// Waits for the remaining signals, to be called
+    /// once the shutdown has been triggered.
+    pub(crate) async fn into_remaining_signals(self) {
+        if self.count <= 1 {
+            return;
+        }
+        if let Some(message) = self.message {
+            tracing::warn!("{}", message);
+        }
+        let listener = self
+            .listener
+            .lock()
+            .unwrap_or_else(|err| err.into_inner())
+            .take();
+        let mut listener = match listener {
+            Some(listener) => listener,
+            None => match self.signals.listen() {
+                Ok(listener) => listener,
+                Err(err) => {
+                    tracing::warn!("::trigger: failed to register signal: {}", err);
+                    return std::future::pending().await;
+                }
+            },
+        };
+        for n in 2..=self.count {
+            let signal = listener.recv().await;
+            tracing::warn!(
+                "::trigger: {} received: escalation {}/{}",
+                signal,
+                n,
+                self.count
+            );
+        }
+    }
+}
+
+/// Returns a [`Future`] that completes once one of the default signals.
+///
+/// Which on Unix is Ctrl-C (sigint) or sigterm,
+/// and on Windows is Ctrl-C, Ctrl-Close or Ctrl-Shutdown.
+///
+/// Exposed to you so you can easily expand it by for example
+/// chaining it with a [`tokio::time::sleep`] to have a delay
+/// before shutdown is triggered.
+///
+/// [`Future`]: std::future::Future
+/// [`tokio::time::sleep`]: https://docs.rs/tokio/*/tokio/time/fn.sleep.html
+#[cfg(all(not(loom), any(unix, windows)))]
+pub async fn default_signal() {
+    if let Ok(mut listener) = SignalSet::Default.listen() {
+        listener.recv().await;
+    }
+}
+
+/// A set of registered Unix signals, which can be received multiple times.
+#[cfg(all(not(loom), unix))]
+#[derive(Debug)]
+pub(crate) struct UnixSignals {
+    signals: Vec<(tokio::signal::unix::SignalKind, tokio::signal::unix::Signal)>,
+}
+
+#[cfg(all(not(loom), unix))]
+impl UnixSignals {
+    /// Registers the given Unix signals,
+    /// returning an error in case one of them could not be registered.
+    pub(crate) fn new(kinds: &[tokio::signal::unix::SignalKind]) -> std::io::Result<Self> {
+        let signals = kinds
+            .iter()
+            .map(|kind| Ok((*kind, tokio::signal::unix::signal(*kind)?)))
+            .collect::<std::io::Result<Vec<_>>>()?;
+        Ok(Self { signals })
+    }
+
+    /// Waits for one of the signals to be received,
+    /// returning the [`OsSignal`] which was received.
+    ///
+    /// This method is cancel safe.
+    pub(crate) async fn recv(&mut self) -> OsSignal {
+        std::future::poll_fn(|cx| {
+            for (kind, signal) in self.signals.iter_mut() {
+                if let std::task::Poll::Ready(Some(())) = signal.poll_recv(cx) {
+                    return std::task::Poll::Ready(OsSignal::Unix(*kind));
+                }
+            }
+            std::task::Poll::Pending
+        })
+        .await
+    }
+}
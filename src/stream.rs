@@ -0,0 +1,141 @@
+use std::{
+    future::Future,
+    pin::Pin,
+    task::{Context, Poll},
+};
+
+use futures_core::{FusedStream, Stream};
+use pin_project_lite::pin_project;
+
+use crate::{trigger::Receiver, WeakShutdownGuard};
+
+pin_project! {
+    /// A [`Stream`] which yields the items of the wrapped stream
+    /// until cancellation (shutdown) is requested and the delay (if any)
+    /// duration has been awaited, after which it ends.
+    ///
+    /// Use [`TakeUntilCancelled::drain_ready`] to instead stop pulling
+    /// new items once the shutdown signal is triggered, while still
+    /// yielding the items which are ready at that point.
+    ///
+    /// Created by [`StreamExt::take_until_cancelled`].
+    #[derive(Debug)]
+    #[must_use = "streams do nothing unless polled"]
+    pub struct TakeUntilCancelled<S> {
+        #[pin]
+        stream: S,
+        cancelled: Receiver,
+        shutdown_signal: Receiver,
+        drain_ready: bool,
+        done: bool,
+    }
+}
+
+impl<S> TakeUntilCancelled<S> {
+    fn new(stream: S, guard: &WeakShutdownGuard) -> Self {
+        Self {
+            stream,
+            cancelled: guard.trigger_rx.clone(),
+            shutdown_signal: guard.shutdown_signal_rx(),
+            drain_ready: false,
+            done: false,
+        }
+    }
+
+    /// Stop pulling new items once the shutdown signal is triggered,
+    /// without awaiting the delay (if any), but still yield the items
+    /// which are ready, ending the stream once the wrapped stream is pending.
+    pub fn drain_ready(mut self) -> Self {
+        self.drain_ready = true;
+        self
+    }
+
+    /// Consumes this adapter, returning the wrapped stream.
+    pub fn into_inner(self) -> S {
+        self.stream
+    }
+}
+
+impl<S: Stream> Stream for TakeUntilCancelled<S> {
+    type Item = S::Item;
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        let this = self.project();
+        if *this.done {
+            return Poll::Ready(None);
+        }
+
+        let cancelled = if *this.drain_ready {
+            Pin::new(this.shutdown_signal).poll(cx)
+        } else {
+            Pin::new(this.cancelled).poll(cx)
+        };
+        if cancelled.is_pending() {
+            let item = futures_core::ready!(this.stream.poll_next(cx));
+            *this.done = item.is_none();
+            return Poll::Ready(item);
+        }
+
+        if !*this.drain_ready {
+            tracing::trace!("::stream: cancelled: end stream");
+            *this.done = true;
+            return Poll::Ready(None);
+        }
+        match this.stream.poll_next(cx) {
+            Poll::Ready(Some(item)) => Poll::Ready(Some(item)),
+            Poll::Ready(None) | Poll::Pending => {
+                tracing::trace!(
+                    "::stream: shutdown signal triggered: ready items drained: end stream"
+                );
+                *this.done = true;
+                Poll::Ready(None)
+            }
+        }
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        if self.done {
+            (0, Some(0))
+        } else {
+            (0, self.stream.size_hint().1)
+        }
+    }
+}
+
+impl<S: Stream> FusedStream for TakeUntilCancelled<S> {
+    fn is_terminated(&self) -> bool {
+        self.done
+    }
+}
+
+/// An extension trait for [`Stream`]s, to take items from them until
+/// the [`Shutdown`] of the given guard is cancelled.
+///
+/// ```
+/// use tokio_graceful::{Shutdown, StreamExt as _};
+/// use tokio_stream::StreamExt;
+///
+/// # async fn example() {
+/// let shutdown = Shutdown::no_signal();
+/// let guard = shutdown.guard();
+/// let mut stream = tokio_stream::iter([1, 2, 3]).take_until_cancelled(&guard);
+/// // a shutdown without a signal is cancelled from the start
+/// assert_eq!(stream.next().await, None);
+/// # }
+/// ```
+///
+/// [`Shutdown`]: crate::Shutdown
+pub trait StreamExt: Stream + Sized {
+    /// Yields the items of this stream until cancellation (shutdown)
+    /// is requested and the delay (if any) duration has been awaited.
+    ///
+    /// See [`TakeUntilCancelled`] for more information.
+    fn take_until_cancelled<G: AsRef<WeakShutdownGuard>>(
+        self,
+        guard: &G,
+    ) -> TakeUntilCancelled<Self> {
+        TakeUntilCancelled::new(self, guard.as_ref())
+    }
+}
+
+impl<S: Stream> StreamExt for S {}
@@ -0,0 +1,85 @@
+//! Integration with the systemd notify protocol (see `sd_notify(3)`),
+//! such that systemd knows when the service is ready, when it is stopping
+//! and that it is still draining, instead of killing it prematurely.
+
+use std::{
+    io,
+    os::unix::{ffi::OsStrExt, net::UnixDatagram},
+    time::Duration,
+};
+
+use crate::ReadinessState;
+
+/// Sends the given state (e.g. `READY=1`) to the systemd service manager,
+/// over the unix datagram socket defined by the `$NOTIFY_SOCKET` environment variable.
+///
+/// Returns `Ok(false)` in case `$NOTIFY_SOCKET` is not defined,
+/// e.g. because the service is not running under systemd.
+///
+/// # Errors
+///
+/// Returns an error in case the state could not be sent over the socket.
+pub fn systemd_notify(state: &str) -> io::Result<bool> {
+    let Some(path) = std::env::var_os("NOTIFY_SOCKET") else {
+        return Ok(false);
+    };
+    let socket = UnixDatagram::unbound()?;
+    match path.as_bytes().strip_prefix(b"@") {
+        // a socket in the abstract namespace
+        #[cfg(target_os = "linux")]
+        Some(name) => {
+            use std::os::linux::net::SocketAddrExt;
+            let addr = std::os::unix::net::SocketAddr::from_abstract_name(name)?;
+            socket.send_to_addr(state.as_bytes(), &addr)?;
+        }
+        _ => {
+            socket.send_to(state.as_bytes(), &path)?;
+        }
+    }
+    tracing::trace!("::systemd: notified: {}", state);
+    Ok(true)
+}
+
+/// Notifies the systemd service manager that the service is ready (`READY=1`).
+///
+/// See [`systemd_notify`] for more information. Use [`ShutdownBuilder::with_systemd_notify`]
+/// instead to notify the service manager once the [`Readiness`] of a [`Shutdown`] is ready.
+///
+/// # Errors
+///
+/// Returns an error in case the state could not be sent over the socket.
+///
+/// [`ShutdownBuilder::with_systemd_notify`]: crate::ShutdownBuilder::with_systemd_notify
+/// [`Readiness`]: crate::Readiness
+/// [`Shutdown`]: crate::Shutdown
+pub fn systemd_notify_ready() -> io::Result<bool> {
+    systemd_notify("READY=1")
+}
+
+/// Notifies the service manager of the given readiness state, if it has a counterpart.
+pub(crate) fn notify_readiness(state: ReadinessState) {
+    let state = match state {
+        ReadinessState::Ready => "READY=1",
+        ReadinessState::Draining => "STOPPING=1",
+        ReadinessState::Starting | ReadinessState::Stopped => return,
+    };
+    if let Err(err) = systemd_notify(state) {
+        tracing::warn!("::systemd: failed to notify {}: {}", state, err);
+    }
+}
+
+/// The time by which the stop timeout of the service is extended while waiting
+/// for the aborted tasks to be dropped, as that wait is not bounded by a time limit.
+pub(crate) const ABORT_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Asks the service manager to extend the stop timeout of the service,
+/// such that it is not killed while it is still draining.
+///
+/// The timeout is relative to the moment it is received,
+/// and thus has to be sent again for each stage of the shutdown.
+pub(crate) fn notify_extend_timeout(timeout: Duration) {
+    let state = format!("EXTEND_TIMEOUT_USEC={}", timeout.as_micros());
+    if let Err(err) = systemd_notify(&state) {
+        tracing::warn!("::systemd: failed to notify {}: {}", state, err);
+    }
+}
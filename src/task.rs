@@ -0,0 +1,407 @@
+use std::{
+    any::Any,
+    fmt,
+    future::Future,
+    panic::{catch_unwind, resume_unwind, AssertUnwindSafe},
+    pin::Pin,
+    task::{Context, Poll},
+    time::Instant,
+};
+
+use pin_project_lite::pin_project;
+use slab::Slab;
+use tokio::task::AbortHandle;
+
+use crate::{
+    sync::{Arc, JoinHandle, Mutex},
+    trigger::{trigger, Receiver, Sender},
+    ShutdownGuard, ShutdownReason, ShutdownTrigger,
+};
+
+/// Describes how a critical task exited,
+/// recorded as the [`ShutdownReason`] of the shutdown it triggered.
+///
+/// See [`ShutdownGuard::spawn_critical_task`] for more information.
+///
+/// [`ShutdownGuard::spawn_critical_task`]: crate::ShutdownGuard::spawn_critical_task
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum CriticalTaskExit {
+    /// The task returned.
+    Returned,
+    /// The task returned an error, containing the error message.
+    ///
+    /// Only recorded for tasks spawned using [`ShutdownGuard::spawn_fallible_critical_task`]
+    /// (or its `_fn` and [`Shutdown`] counterparts).
+    ///
+    /// [`ShutdownGuard::spawn_fallible_critical_task`]: crate::ShutdownGuard::spawn_fallible_critical_task
+    /// [`Shutdown`]: crate::Shutdown
+    Errored(String),
+    /// The task panicked, containing the panic message if it was a string.
+    Panicked(Option<String>),
+    /// The task was aborted (e.g. the runtime shut down) before it completed.
+    Aborted,
+}
+
+impl fmt::Display for CriticalTaskExit {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            CriticalTaskExit::Returned => write!(f, "returned"),
+            CriticalTaskExit::Errored(msg) => write!(f, "errored: {}", msg),
+            CriticalTaskExit::Panicked(Some(msg)) => write!(f, "panicked: {}", msg),
+            CriticalTaskExit::Panicked(None) => write!(f, "panicked"),
+            CriticalTaskExit::Aborted => write!(f, "aborted"),
+        }
+    }
+}
+
+pin_project! {
+    /// A future wrapping a critical task, triggering the shutdown
+    /// once the task returns (or errors), panics or gets dropped before completion.
+    pub(crate) struct CriticalTask<F: Future> {
+        #[pin]
+        task: F,
+        shutdown_trigger: Option<ShutdownTrigger>,
+        exit: fn(&F::Output) -> CriticalTaskExit,
+    }
+
+    impl<F: Future> PinnedDrop for CriticalTask<F> {
+        fn drop(this: Pin<&mut Self>) {
+            if let Some(shutdown_trigger) = this.project().shutdown_trigger.take() {
+                trigger_shutdown(shutdown_trigger, CriticalTaskExit::Aborted);
+            }
+        }
+    }
+}
+
+impl<F: Future> CriticalTask<F> {
+    pub(crate) fn new(task: F, shutdown_trigger: ShutdownTrigger) -> Self {
+        Self {
+            task,
+            shutdown_trigger: Some(shutdown_trigger),
+            exit: |_| CriticalTaskExit::Returned,
+        }
+    }
+}
+
+impl<F, T, E> CriticalTask<F>
+where
+    F: Future<Output = Result<T, E>>,
+    E: fmt::Display,
+{
+    /// Creates a critical task which records the error it returned (if any).
+    pub(crate) fn fallible(task: F, shutdown_trigger: ShutdownTrigger) -> Self {
+        Self {
+            task,
+            shutdown_trigger: Some(shutdown_trigger),
+            exit: |output| match output {
+                Ok(_) => CriticalTaskExit::Returned,
+                Err(err) => CriticalTaskExit::Errored(err.to_string()),
+            },
+        }
+    }
+}
+
+impl<F: Future> Future for CriticalTask<F> {
+    type Output = F::Output;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        let mut this = self.project();
+        match catch_unwind(AssertUnwindSafe(|| this.task.as_mut().poll(cx))) {
+            Ok(Poll::Pending) => Poll::Pending,
+            Ok(Poll::Ready(output)) => {
+                if let Some(shutdown_trigger) = this.shutdown_trigger.take() {
+                    trigger_shutdown(shutdown_trigger, (this.exit)(&output));
+                }
+                Poll::Ready(output)
+            }
+            Err(panic) => {
+                if let Some(shutdown_trigger) = this.shutdown_trigger.take() {
+                    let exit = CriticalTaskExit::Panicked(panic_message(panic.as_ref()));
+                    trigger_shutdown(shutdown_trigger, exit);
+                }
+                resume_unwind(panic)
+            }
+        }
+    }
+}
+
+fn trigger_shutdown(shutdown_trigger: ShutdownTrigger, exit: CriticalTaskExit) {
+    if shutdown_trigger.reason().is_some() {
+        tracing::trace!("::critical task: {}: shutdown already triggered", exit);
+        return;
+    }
+    tracing::warn!("::critical task: {}: triggering shutdown", exit);
+    shutdown_trigger.trigger_with_reason(ShutdownReason::CriticalTask(exit));
+}
+
+fn panic_message(panic: &(dyn Any + Send)) -> Option<String> {
+    panic
+        .downcast_ref::<&str>()
+        .map(|msg| (*msg).to_owned())
+        .or_else(|| panic.downcast_ref::<String>().cloned())
+}
+
+/// The span of a task spawned using a [`ShutdownGuard`],
+/// carrying the name of the guard in case it is named.
+///
+/// The span is attached to the guard handed to the task,
+/// such that the release of that guard is traced within the span.
+///
+/// See [`ShutdownGuard::named`] for more information on how to name a guard.
+///
+/// [`ShutdownGuard`]: crate::ShutdownGuard
+/// [`ShutdownGuard::named`]: crate::ShutdownGuard::named
+#[derive(Debug, Clone)]
+pub(crate) struct TaskSpan {
+    span: tracing::Span,
+    cancelled: Receiver,
+    shutdown_trigger: ShutdownTrigger,
+    spawned_at: Instant,
+}
+
+impl TaskSpan {
+    pub(crate) fn new(guard: &ShutdownGuard) -> Self {
+        let span = match guard.name() {
+            Some(name) => tracing::debug_span!("task", name = %name),
+            None => tracing::debug_span!("task", name = tracing::field::Empty),
+        };
+        Self {
+            span,
+            cancelled: guard.weak().trigger_rx.clone(),
+            shutdown_trigger: guard.shutdown_trigger(),
+            spawned_at: Instant::now(),
+        }
+    }
+
+    /// Instruments the given task with this span.
+    pub(crate) fn instrument<F>(self, task: F) -> TracedTask<F> {
+        TracedTask {
+            task,
+            span: self,
+            polled_after_cancellation: false,
+        }
+    }
+
+    /// Traces the release of the guard handed to the task,
+    /// which can happen before the task itself completes.
+    pub(crate) fn released(&self) {
+        let _enter = self.span.enter();
+        let held = self.spawned_at.elapsed();
+        if self.cancelled.is_triggered() {
+            tracing::debug!(
+                "::task: released guard {}s after cancellation (held for {}s)",
+                self.shutdown_trigger
+                    .elapsed_since_cancelled()
+                    .as_secs_f64(),
+                held.as_secs_f64()
+            );
+        } else {
+            tracing::trace!("::task: released guard (held for {}s)", held.as_secs_f64());
+        }
+    }
+}
+
+pin_project! {
+    /// A future wrapping a task spawned using a guard, polled within its [`TaskSpan`],
+    /// tracing the first time it is polled after the cancellation.
+    ///
+    /// A task which is not polled anymore after the cancellation
+    /// (e.g. because it completed just before) does not trace this.
+    pub(crate) struct TracedTask<F> {
+        #[pin]
+        task: F,
+        span: TaskSpan,
+        polled_after_cancellation: bool,
+    }
+}
+
+impl<F: Future> Future for TracedTask<F> {
+    type Output = F::Output;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        let this = self.project();
+        let _enter = this.span.span.enter();
+        if !*this.polled_after_cancellation && this.span.cancelled.is_triggered() {
+            *this.polled_after_cancellation = true;
+            tracing::debug!(
+                "::task: first polled {}s after cancellation",
+                this.span
+                    .shutdown_trigger
+                    .elapsed_since_cancelled()
+                    .as_secs_f64()
+            );
+        }
+        this.task.poll(cx)
+    }
+}
+
+/// Tracks the tasks spawned using the guards of a [`Shutdown`],
+/// such that they can be aborted once its time limit is reached.
+///
+/// See [`ShutdownBuilder::with_abort_on_timeout`] for more information.
+///
+/// [`Shutdown`]: crate::Shutdown
+/// [`ShutdownBuilder::with_abort_on_timeout`]: crate::ShutdownBuilder::with_abort_on_timeout
+#[derive(Debug, Clone, Default)]
+pub(crate) struct TaskTracker {
+    state: Arc<Mutex<TrackerState>>,
+}
+
+#[derive(Debug, Default)]
+struct TrackerState {
+    next_id: u64,
+    tasks: Slab<TrackedTask>,
+    empty_tx: Option<Sender>,
+    aborting: bool,
+    aborted: usize,
+}
+
+#[derive(Debug)]
+struct TrackedTask {
+    id: u64,
+    abort_handle: Option<AbortHandle>,
+}
+
+/// Removes the tracked task once its future is dropped,
+/// which is when it completed or once its abort took effect.
+///
+/// A task which is dropped before it completed while the tasks are being aborted
+/// is counted as aborted, such that tasks which completed on their own are not.
+pub(crate) struct TrackedTaskToken {
+    key: usize,
+    id: u64,
+    state: Arc<Mutex<TrackerState>>,
+    completed: bool,
+}
+
+impl TrackedTaskToken {
+    pub(crate) fn key(&self) -> (usize, u64) {
+        (self.key, self.id)
+    }
+
+    /// Marks the task as completed, removing it from the tracked tasks.
+    pub(crate) fn complete(mut self) {
+        self.completed = true;
+    }
+}
+
+impl Drop for TrackedTaskToken {
+    fn drop(&mut self) {
+        let mut state = self.state.lock().unwrap();
+        if state.tasks.get(self.key).map(|task| task.id) == Some(self.id) {
+            state.tasks.remove(self.key);
+            // a task which panicked was not aborted either
+            if state.aborting && !self.completed && !std::thread::panicking() {
+                state.aborted += 1;
+            }
+        }
+        if state.tasks.is_empty() {
+            if let Some(empty_tx) = state.empty_tx.take() {
+                empty_tx.trigger();
+            }
+        }
+    }
+}
+
+impl TaskTracker {
+    /// Spawns the task, tracking it until its future is dropped.
+    pub(crate) fn spawn<T>(&self, task: T) -> JoinHandle<T::Output>
+    where
+        T: Future + Send + 'static,
+        T::Output: Send + 'static,
+    {
+        // the task is registered prior to spawning it,
+        // as it is not safe to spawn while holding the lock
+        let token = self.register();
+        let (key, id) = token.key();
+        let handle = crate::sync::spawn(async move {
+            let output = task.await;
+            token.complete();
+            output
+        });
+        self.attach(key, id, handle.abort_handle());
+        handle
+    }
+
+    /// Registers a task which is about to be spawned,
+    /// returning the token which is to be moved into the task.
+    pub(crate) fn register(&self) -> TrackedTaskToken {
+        let mut state = self.state.lock().unwrap();
+        let id = state.next_id;
+        state.next_id += 1;
+        let key = state.tasks.insert(TrackedTask {
+            id,
+            abort_handle: None,
+        });
+        TrackedTaskToken {
+            key,
+            id,
+            state: self.state.clone(),
+            completed: false,
+        }
+    }
+
+    /// Attaches the abort handle to the registered task, once spawned,
+    /// aborting the task right away in case the tasks are being aborted.
+    pub(crate) fn attach(&self, key: usize, id: u64, abort_handle: AbortHandle) {
+        let mut state = self.state.lock().unwrap();
+        let aborting = state.aborting;
+        let Some(task) = state.tasks.get_mut(key).filter(|task| task.id == id) else {
+            // the task already completed
+            return;
+        };
+        if !aborting {
+            task.abort_handle = Some(abort_handle);
+            return;
+        }
+        drop(state);
+        // aborted outside of the lock, as the task might be dropped in place
+        abort_handle.abort();
+    }
+
+    /// Aborts all tracked tasks, returning the amount of tasks aborted by this call
+    /// once all of them are confirmed to be dropped, not counting the tasks
+    /// which completed on their own in the meantime.
+    ///
+    /// Tasks which are still being spawned are aborted as soon as they are attached.
+    pub(crate) async fn abort_all(&self) -> usize {
+        let (abort_handles, empty_rx) = {
+            let mut state = self.state.lock().unwrap();
+            state.aborting = true;
+            state.aborted = 0;
+            if state.tasks.is_empty() {
+                return 0;
+            }
+            let abort_handles: Vec<_> = state
+                .tasks
+                .iter()
+                .filter_map(|(_, task)| task.abort_handle.clone())
+                .collect();
+            let (empty_tx, empty_rx) = trigger();
+            state.empty_tx = Some(empty_tx);
+            (abort_handles, empty_rx)
+        };
+        tracing::warn!("::shutdown: aborting {} task(s)", abort_handles.len());
+        // aborted outside of the lock, as the task might be dropped in place
+        for abort_handle in abort_handles {
+            abort_handle.abort();
+        }
+        empty_rx.await;
+        let aborted = self.state.lock().unwrap().aborted;
+        tracing::trace!("::shutdown: all {} task(s) aborted", aborted);
+        aborted
+    }
+}
+
+/// Spawns the task, tracking it in case a [`TaskTracker`] is given.
+pub(crate) fn spawn_task<T>(task_tracker: Option<&TaskTracker>, task: T) -> JoinHandle<T::Output>
+where
+    T: Future + Send + 'static,
+    T::Output: Send + 'static,
+{
+    match task_tracker {
+        Some(task_tracker) => task_tracker.spawn(task),
+        None => crate::sync::spawn(task),
+    }
+}
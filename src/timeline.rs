@@ -0,0 +1,420 @@
+//! A machine-readable timeline of a [`Shutdown`], recorded when enabled
+//! using [`ShutdownBuilder::with_timeline`], which can be exported as JSON
+//! or in the Chrome trace event format (e.g. to view it in Perfetto).
+//!
+//! [`Shutdown`]: crate::Shutdown
+//! [`ShutdownBuilder::with_timeline`]: crate::ShutdownBuilder::with_timeline
+
+use std::{
+    borrow::Cow,
+    fmt::{self, Write as _},
+    io,
+    path::Path,
+    time::{Duration, Instant, SystemTime},
+};
+
+use crate::{
+    sync::{Arc, AtomicBool, Mutex, Ordering},
+    DrainOutcome, ShutdownReason,
+};
+
+/// The timeline of a [`Shutdown`], available using [`ShutdownReport::timeline`]
+/// in case it was enabled using [`ShutdownBuilder::with_timeline`].
+///
+/// It can be exported as JSON using [`Timeline::to_json`] and in the
+/// [Chrome trace event format] using [`Timeline::to_chrome_trace`], e.g. to view it
+/// in [Perfetto](https://ui.perfetto.dev), or written to a file directly using
+/// [`Timeline::write_json`] and [`Timeline::write_chrome_trace`].
+///
+/// ```
+/// use tokio_graceful::Shutdown;
+///
+/// # async fn example() {
+/// let shutdown = Shutdown::builder()
+///     .with_signal(tokio::time::sleep(std::time::Duration::from_millis(10)))
+///     .with_timeline()
+///     .build();
+/// let report = shutdown.shutdown().await;
+/// let timeline = report.timeline().unwrap();
+/// timeline.write_chrome_trace("shutdown.trace.json").unwrap();
+/// # }
+/// ```
+///
+/// [`Shutdown`]: crate::Shutdown
+/// [`ShutdownReport::timeline`]: crate::ShutdownReport::timeline
+/// [`ShutdownBuilder::with_timeline`]: crate::ShutdownBuilder::with_timeline
+/// [Chrome trace event format]: https://docs.google.com/document/d/1CvAClvFfyA5R-PhYUmn5OOQtYMH4h6I0nSsKchNAySU
+#[derive(Debug, Clone)]
+pub struct Timeline {
+    started_at: SystemTime,
+    events: Vec<TimelineEvent>,
+}
+
+/// An event of a [`Timeline`].
+#[derive(Debug, Clone)]
+pub struct TimelineEvent {
+    elapsed: Duration,
+    kind: TimelineEventKind,
+}
+
+/// The kind of a [`TimelineEvent`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum TimelineEventKind {
+    /// The shutdown was triggered for the given reason.
+    SignalReceived(ShutdownReason),
+    /// The delay (if any, otherwise it is [`Duration::ZERO`]) started.
+    DelayStarted(Duration),
+    /// The delay ended, after which the guards are cancelled.
+    DelayEnded,
+    /// The cancellation of the guards was broadcast,
+    /// after which the [`Shutdown`] waits for the remaining guards to be dropped.
+    ///
+    /// [`Shutdown`]: crate::Shutdown
+    Cancelled,
+    /// A guard was released (dropped).
+    GuardReleased {
+        /// The scope of the guard, which is `main` for the guards of the [`Shutdown`]
+        /// itself, or the name of the phase for the guards of a phase
+        /// (see [`ShutdownBuilder::with_phase`]).
+        ///
+        /// [`Shutdown`]: crate::Shutdown
+        /// [`ShutdownBuilder::with_phase`]: crate::ShutdownBuilder::with_phase
+        scope: Cow<'static, str>,
+        /// The name of the guard, see [`ShutdownGuard::named`].
+        ///
+        /// [`ShutdownGuard::named`]: crate::ShutdownGuard::named
+        name: Option<Cow<'static, str>>,
+        /// The amount of guards which are still alive.
+        remaining_guards: usize,
+    },
+    /// A phase started, see [`ShutdownBuilder::with_phase`].
+    ///
+    /// [`ShutdownBuilder::with_phase`]: crate::ShutdownBuilder::with_phase
+    PhaseStarted {
+        /// The name of the phase.
+        name: Cow<'static, str>,
+    },
+    /// A phase ended.
+    PhaseEnded {
+        /// The name of the phase.
+        name: Cow<'static, str>,
+        /// How the phase ended.
+        outcome: DrainOutcome,
+    },
+    /// The shutdown finished, with the outcome of the shutdown as a whole:
+    /// the outcome of its drain stage in case it did not complete,
+    /// or otherwise the outcome of the first phase which did not complete (if any).
+    ///
+    /// This outcome is [`DrainOutcome::Completed`] only if
+    /// [`ShutdownReport::is_graceful`] returns `true`.
+    ///
+    /// [`ShutdownReport::is_graceful`]: crate::ShutdownReport::is_graceful
+    Finished(DrainOutcome),
+}
+
+impl Timeline {
+    /// Returns the (system) time at which the recording of the timeline started,
+    /// which is when the [`Shutdown`] was built.
+    ///
+    /// [`Shutdown`]: crate::Shutdown
+    pub fn started_at(&self) -> SystemTime {
+        self.started_at
+    }
+
+    /// Returns the recorded events, in the order they happened.
+    pub fn events(&self) -> &[TimelineEvent] {
+        &self.events
+    }
+
+    /// Returns the timeline as a JSON object, containing the `started_at` time
+    /// (in seconds since the unix epoch) and the recorded `events`, each with the
+    /// time `elapsed` since the start (in seconds), the kind of `event`
+    /// and its properties.
+    pub fn to_json(&self) -> String {
+        let started_at = self
+            .started_at
+            .duration_since(SystemTime::UNIX_EPOCH)
+            .unwrap_or_default();
+        let mut json = format!("{{\"started_at\":{},\"events\":[", started_at.as_secs_f64());
+        for (index, event) in self.events.iter().enumerate() {
+            if index > 0 {
+                json.push(',');
+            }
+            let _ = write!(
+                json,
+                "{{\"elapsed\":{},\"event\":\"{}\"",
+                event.elapsed.as_secs_f64(),
+                event.kind.name()
+            );
+            event.kind.write_json_args(&mut json);
+            json.push('}');
+        }
+        json.push_str("]}");
+        json
+    }
+
+    /// Returns the timeline in the Chrome trace event format,
+    /// with the delay, drain and phases as duration events
+    /// and all other events as instant events.
+    pub fn to_chrome_trace(&self) -> String {
+        let mut json = String::from("{\"traceEvents\":[");
+        let mut first = true;
+        let mut push = |json: &mut String, name: &str, phase: &str, ts: Duration, args: &str| {
+            if !first {
+                json.push(',');
+            }
+            first = false;
+            let _ = write!(
+                json,
+                "{{\"name\":{},\"cat\":\"shutdown\",\"ph\":\"{}\",\"ts\":{},\"pid\":1,\"tid\":1{}",
+                JsonStr(name),
+                phase,
+                ts.as_micros(),
+                if phase == "i" { ",\"s\":\"p\"" } else { "" },
+            );
+            if !args.is_empty() {
+                let _ = write!(json, ",\"args\":{{{}}}", args.trim_start_matches(','));
+            }
+            json.push('}');
+        };
+
+        // the delay and drain are ended explicitly in case they were cut short
+        let (mut delaying, mut draining) = (false, false);
+        for event in &self.events {
+            let mut args = String::new();
+            event.kind.write_json_args(&mut args);
+            if matches!(
+                event.kind,
+                TimelineEventKind::PhaseStarted { .. } | TimelineEventKind::Finished(_)
+            ) {
+                if delaying {
+                    push(&mut json, "delay", "E", event.elapsed, "");
+                    delaying = false;
+                }
+                if draining {
+                    push(&mut json, "drain", "E", event.elapsed, "");
+                    draining = false;
+                }
+            }
+            let (name, phase) = match &event.kind {
+                TimelineEventKind::DelayStarted(_) => {
+                    delaying = true;
+                    (Cow::Borrowed("delay"), "B")
+                }
+                TimelineEventKind::DelayEnded if delaying => {
+                    delaying = false;
+                    (Cow::Borrowed("delay"), "E")
+                }
+                TimelineEventKind::Cancelled => {
+                    push(&mut json, "cancelled", "i", event.elapsed, &args);
+                    draining = true;
+                    (Cow::Borrowed("drain"), "B")
+                }
+                TimelineEventKind::PhaseStarted { name } => {
+                    (Cow::Owned(format!("phase: {}", name)), "B")
+                }
+                TimelineEventKind::PhaseEnded { name, .. } => {
+                    (Cow::Owned(format!("phase: {}", name)), "E")
+                }
+                kind => (Cow::Borrowed(kind.name()), "i"),
+            };
+            push(&mut json, &name, phase, event.elapsed, &args);
+        }
+        json.push_str("],\"displayTimeUnit\":\"ms\"}");
+        json
+    }
+
+    /// Writes the timeline as JSON (see [`Timeline::to_json`]) to the given file.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error in case the file could not be written.
+    pub fn write_json(&self, path: impl AsRef<Path>) -> io::Result<()> {
+        std::fs::write(path, self.to_json())
+    }
+
+    /// Writes the timeline in the Chrome trace event format
+    /// (see [`Timeline::to_chrome_trace`]) to the given file.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error in case the file could not be written.
+    pub fn write_chrome_trace(&self, path: impl AsRef<Path>) -> io::Result<()> {
+        std::fs::write(path, self.to_chrome_trace())
+    }
+}
+
+impl TimelineEvent {
+    /// Returns the time elapsed between the start of the [`Timeline`] and this event.
+    pub fn elapsed(&self) -> Duration {
+        self.elapsed
+    }
+
+    /// Returns the kind of this event.
+    pub fn kind(&self) -> &TimelineEventKind {
+        &self.kind
+    }
+}
+
+impl TimelineEventKind {
+    fn name(&self) -> &'static str {
+        match self {
+            TimelineEventKind::SignalReceived(_) => "signal_received",
+            TimelineEventKind::DelayStarted(_) => "delay_started",
+            TimelineEventKind::DelayEnded => "delay_ended",
+            TimelineEventKind::Cancelled => "cancelled",
+            TimelineEventKind::GuardReleased { .. } => "guard_released",
+            TimelineEventKind::PhaseStarted { .. } => "phase_started",
+            TimelineEventKind::PhaseEnded { .. } => "phase_ended",
+            TimelineEventKind::Finished(_) => "finished",
+        }
+    }
+
+    /// Writes the properties of the event as JSON object members,
+    /// each prefixed with a comma.
+    fn write_json_args(&self, json: &mut String) {
+        let _ = match self {
+            TimelineEventKind::SignalReceived(reason) => {
+                write!(json, ",\"reason\":{}", JsonStr(&reason.to_string()))
+            }
+            TimelineEventKind::DelayStarted(delay) => {
+                write!(json, ",\"delay\":{}", delay.as_secs_f64())
+            }
+            TimelineEventKind::DelayEnded | TimelineEventKind::Cancelled => Ok(()),
+            TimelineEventKind::GuardReleased {
+                scope,
+                name,
+                remaining_guards,
+            } => match name {
+                Some(name) => write!(
+                    json,
+                    ",\"scope\":{},\"name\":{},\"remaining_guards\":{}",
+                    JsonStr(scope),
+                    JsonStr(name),
+                    remaining_guards
+                ),
+                None => write!(
+                    json,
+                    ",\"scope\":{},\"name\":null,\"remaining_guards\":{}",
+                    JsonStr(scope),
+                    remaining_guards
+                ),
+            },
+            TimelineEventKind::PhaseStarted { name } => {
+                write!(json, ",\"name\":{}", JsonStr(name))
+            }
+            TimelineEventKind::PhaseEnded { name, outcome } => write!(
+                json,
+                ",\"name\":{},\"outcome\":\"{}\"",
+                JsonStr(name),
+                outcome_name(*outcome)
+            ),
+            TimelineEventKind::Finished(outcome) => {
+                write!(json, ",\"outcome\":\"{}\"", outcome_name(*outcome))
+            }
+        };
+    }
+}
+
+fn outcome_name(outcome: DrainOutcome) -> &'static str {
+    match outcome {
+        DrainOutcome::Completed => "completed",
+        DrainOutcome::TimedOut => "timed_out",
+        DrainOutcome::Overwritten => "overwritten",
+    }
+}
+
+/// Formats a string as a (quoted and escaped) JSON string.
+struct JsonStr<'a>(&'a str);
+
+impl fmt::Display for JsonStr<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_char('"')?;
+        for c in self.0.chars() {
+            match c {
+                '"' => f.write_str("\\\"")?,
+                '\\' => f.write_str("\\\\")?,
+                '\n' => f.write_str("\\n")?,
+                '\r' => f.write_str("\\r")?,
+                '\t' => f.write_str("\\t")?,
+                c if c.is_control() => write!(f, "\\u{:04x}", c as u32)?,
+                c => f.write_char(c)?,
+            }
+        }
+        f.write_char('"')
+    }
+}
+
+/// Records the [`Timeline`] of a [`Shutdown`],
+/// shared between all clones of its [`ShutdownTrigger`].
+///
+/// Guard releases, of the [`Shutdown`] as well as of its phases, are only recorded
+/// once the first event was recorded, which is when the shutdown was triggered
+/// (or the guards were cancelled), such that the timeline does not grow
+/// while the [`Shutdown`] is running.
+///
+/// [`Shutdown`]: crate::Shutdown
+/// [`ShutdownTrigger`]: crate::ShutdownTrigger
+#[derive(Debug, Clone)]
+pub(crate) struct TimelineRecorder {
+    start: Instant,
+    started_at: SystemTime,
+    events: Arc<Mutex<Vec<TimelineEvent>>>,
+    recording: Arc<AtomicBool>,
+}
+
+impl Default for TimelineRecorder {
+    fn default() -> Self {
+        Self {
+            start: Instant::now(),
+            started_at: SystemTime::now(),
+            events: Default::default(),
+            recording: Default::default(),
+        }
+    }
+}
+
+impl TimelineRecorder {
+    // the elapsed time is taken while holding the lock,
+    // such that the events are recorded in chronological order
+    pub(crate) fn record(&self, kind: TimelineEventKind) {
+        let mut events = self.events.lock().unwrap();
+        let elapsed = self.start.elapsed();
+        events.push(TimelineEvent { elapsed, kind });
+        self.recording.store(true, Ordering::Release);
+    }
+
+    /// Releases a guard using the given function, which returns the amount of
+    /// remaining guards, and records it. The guard is released while holding the lock,
+    /// such that the releases are recorded in the order they happened.
+    pub(crate) fn record_guard_released(
+        &self,
+        scope: Cow<'static, str>,
+        name: Option<Cow<'static, str>>,
+        release: impl FnOnce() -> usize,
+    ) -> usize {
+        if !self.recording.load(Ordering::Acquire) {
+            return release();
+        }
+        let mut events = self.events.lock().unwrap();
+        let remaining_guards = release();
+        let elapsed = self.start.elapsed();
+        events.push(TimelineEvent {
+            elapsed,
+            kind: TimelineEventKind::GuardReleased {
+                scope,
+                name,
+                remaining_guards,
+            },
+        });
+        remaining_guards
+    }
+
+    pub(crate) fn timeline(&self) -> Timeline {
+        Timeline {
+            started_at: self.started_at,
+            events: self.events.lock().unwrap().clone(),
+        }
+    }
+}
@@ -24,7 +24,7 @@ use std::{
 use pin_project_lite::pin_project;
 use slab::Slab;
 
-use crate::sync::{Arc, AtomicBool, Mutex, Ordering};
+use crate::sync::{Arc, AtomicBool, AtomicUsize, Mutex, Ordering};
 
 type WakerList = Arc<Mutex<Slab<Option<Waker>>>>;
 type TriggerState = Arc<AtomicBool>;
@@ -163,6 +163,19 @@ impl Receiver {
             state: ReceiverState::Pending,
         }
     }
+
+    /// Returns whether the Receiver has been triggered,
+    /// without registering a waker.
+    ///
+    /// A closed Receiver is always triggered,
+    /// while a pending Receiver never is.
+    pub(crate) fn is_triggered(&self) -> bool {
+        match &self.state {
+            ReceiverState::Open { sub, .. } => sub.state.load(Ordering::SeqCst),
+            ReceiverState::Closed => true,
+            ReceiverState::Pending => false,
+        }
+    }
 }
 
 impl Future for Receiver {
@@ -239,6 +252,135 @@ pub fn trigger() -> (Sender, Receiver) {
     (sender, receiver)
 }
 
+/// A [`GenerationSender`] is a [`Sender`] which can be triggered multiple times,
+/// by incrementing its generation and waking up all the wakers.
+///
+/// Each [`GenerationReceiver`] keeps track of the last generation it has seen,
+/// meaning that multiple triggers in between two polls are coalesced
+/// into a single event for that receiver.
+#[derive(Debug, Clone)]
+pub struct GenerationSender {
+    generation: Arc<AtomicUsize>,
+    wakers: WakerList,
+}
+
+impl GenerationSender {
+    /// Triggers all (current) receivers, by incrementing the generation.
+    pub fn trigger(&self) {
+        let generation = self.generation.fetch_add(1, Ordering::SeqCst);
+        tracing::trace!("trigger::GenerationSender: generation: {}", generation + 1);
+
+        let mut wakers = self.wakers.lock().unwrap();
+        for (key, waker) in wakers.iter_mut() {
+            if let Some(waker) = waker.take() {
+                tracing::trace!("trigger::GenerationSender: wake up waker with key: {}", key);
+                waker.wake();
+            }
+        }
+    }
+
+    /// Creates a new [`GenerationReceiver`], which will only
+    /// be triggered for generations after the current one.
+    pub fn subscribe(&self) -> GenerationReceiver {
+        GenerationReceiver {
+            generation: self.generation.clone(),
+            wakers: self.wakers.clone(),
+            seen: self.generation.load(Ordering::SeqCst),
+            key: None,
+        }
+    }
+}
+
+/// The receiving end of a [`GenerationSender`].
+#[derive(Debug)]
+pub struct GenerationReceiver {
+    generation: Arc<AtomicUsize>,
+    wakers: WakerList,
+    seen: usize,
+    key: Option<usize>,
+}
+
+impl Clone for GenerationReceiver {
+    /// Clones the receiver, including the last seen generation,
+    /// but not its key as that is linked to the original receiver.
+    fn clone(&self) -> Self {
+        Self {
+            generation: self.generation.clone(),
+            wakers: self.wakers.clone(),
+            seen: self.seen,
+            key: None,
+        }
+    }
+}
+
+impl GenerationReceiver {
+    /// Polls whether a new generation has been triggered
+    /// since the last generation seen by this receiver.
+    pub fn poll_changed(&mut self, cx: &mut Context) -> Poll<()> {
+        if Self::observe(&self.generation, &mut self.seen) {
+            return Poll::Ready(());
+        }
+
+        let mut wakers = self.wakers.lock().unwrap();
+
+        // check again after locking the wakers,
+        // as to not miss a trigger in between (same as for `Subscriber::state`)
+        if Self::observe(&self.generation, &mut self.seen) {
+            return Poll::Ready(());
+        }
+
+        let waker = Some(cx.waker().clone());
+        match self.key {
+            Some(key) => *wakers.get_mut(key).unwrap() = waker,
+            None => {
+                let key = wakers.insert(waker);
+                tracing::trace!("trigger::GenerationReceiver: insert waker for key: {}", key);
+                self.key = Some(key);
+            }
+        }
+        Poll::Pending
+    }
+
+    /// Waits until a new generation has been triggered.
+    ///
+    /// This method is cancel safe.
+    pub async fn changed(&mut self) {
+        std::future::poll_fn(|cx| self.poll_changed(cx)).await
+    }
+
+    /// Marks the current generation as seen,
+    /// returning `true` if it was not seen before.
+    fn observe(generation: &AtomicUsize, seen: &mut usize) -> bool {
+        let generation = generation.load(Ordering::SeqCst);
+        if generation == *seen {
+            return false;
+        }
+        *seen = generation;
+        true
+    }
+}
+
+impl Drop for GenerationReceiver {
+    /// Remove the waker from the waker list, as to ensure the receiver is cancel safe.
+    fn drop(&mut self) {
+        if let Some(key) = self.key.take() {
+            let mut wakers = self.wakers.lock().unwrap();
+            tracing::trace!(
+                "trigger::GenerationReceiver::Drop: remove waker for key: {}",
+                key
+            );
+            wakers.remove(key);
+        }
+    }
+}
+
+pub fn generation_trigger() -> GenerationSender {
+    GenerationSender {
+        generation: Arc::new(AtomicUsize::new(0)),
+        wakers: Arc::new(Mutex::new(Slab::new())),
+    }
+}
+
 #[cfg(all(test, not(loom)))]
 mod tests {
     use super::*;
@@ -263,6 +405,38 @@ mod tests {
             .await
             .unwrap_err();
     }
+
+    #[test]
+    fn test_receiver_is_triggered() {
+        let (sender, receiver) = trigger();
+        assert!(!receiver.is_triggered());
+        sender.trigger();
+        assert!(receiver.is_triggered());
+
+        assert!(Receiver::closed().is_triggered());
+        assert!(!Receiver::pending().is_triggered());
+    }
+
+    #[tokio::test]
+    async fn test_generation_sender_trigger() {
+        let sender = generation_trigger();
+        let mut receiver = sender.subscribe();
+
+        sender.trigger();
+        sender.trigger();
+        receiver.changed().await;
+        // both triggers are coalesced into a single change
+        tokio::time::timeout(std::time::Duration::from_millis(10), receiver.changed())
+            .await
+            .unwrap_err();
+
+        let th = tokio::spawn(async move {
+            sender.trigger();
+        });
+        receiver.changed().await;
+
+        th.await.unwrap();
+    }
 }
 
 #[cfg(all(test, loom))]
@@ -287,4 +461,22 @@ mod loom_tests {
             th.join().unwrap();
         });
     }
+
+    #[test]
+    fn test_loom_generation_sender_trigger() {
+        loom::model(|| {
+            let sender = generation_trigger();
+            let mut receiver = sender.subscribe();
+
+            let th = thread::spawn(move || {
+                sender.trigger();
+            });
+
+            block_on(async move {
+                receiver.changed().await;
+            });
+
+            th.join().unwrap();
+        });
+    }
 }